@@ -0,0 +1,72 @@
+use std::fmt;
+
+// what is wrong with the input, `InputError` says where
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    // the value isn't a whole number of its type
+    NotANumber { what: &'static str, text: String },
+    // the line ends before the value
+    Missing(&'static str),
+    // the line goes on after the last value
+    TooManyValues,
+    OutOfRange { what: &'static str, value: i64, min: i64, max: i64 },
+    // off the walls and outside of a polygon room
+    OutsideOfTheRoom { y: i32, x: i32 },
+    // devices by their index in the task
+    DuplicateDevice { first: usize, second: usize, y: i32, x: i32 },
+    ObstacleTouchesTheWalls,
+    ObstacleWithoutArea,
+    // obstacles by their order in the task, from 1
+    ObstaclesTouch(usize, usize),
+    // edges by their first corner, from 1
+    EdgeWithoutLength(usize),
+    EdgesCross(usize, usize),
+    RoomWithoutArea,
+    // the first line says a different number of tasks than there are
+    TaskCount { expected: usize, found: usize },
+    // the input ends in the middle of a task
+    UnexpectedEnd
+}
+
+// error in task `task` (0 before the first one) on line `line` of the input, both from 1
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InputError {
+    pub task: usize,
+    pub line: usize,
+    pub kind: ErrorKind
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::NotANumber { what, text } => write!(f, "\"{}\" is \"{}\", not a whole number that fits", what, text),
+            ErrorKind::Missing(what) => write!(f, "\"{}\" is missing", what),
+            ErrorKind::TooManyValues => write!(f, "there is more on the line than expected"),
+            ErrorKind::OutOfRange { what, value, min, max } => {
+                write!(f, "\"{}\" is {}, it has to be from {} to {}", what, value, min, max)
+            }
+            ErrorKind::OutsideOfTheRoom { y, x } => write!(f, "device ({}, {}) is outside of the room", y, x),
+            ErrorKind::DuplicateDevice { first, second, y, x } => {
+                write!(f, "devices {} and {} are both at ({}, {})", first, second, y, x)
+            }
+            ErrorKind::ObstacleTouchesTheWalls => write!(f, "the obstacle touches the walls"),
+            ErrorKind::ObstacleWithoutArea => write!(f, "the obstacle has no area"),
+            ErrorKind::ObstaclesTouch(first, second) => write!(f, "obstacles {} and {} touch", first, second),
+            ErrorKind::EdgeWithoutLength(edge) => write!(f, "edge {} of the room has no length", edge),
+            ErrorKind::EdgesCross(first, second) => write!(f, "edges {} and {} of the room cross", first, second),
+            ErrorKind::RoomWithoutArea => write!(f, "the room has no area"),
+            ErrorKind::TaskCount { expected, found } => write!(f, "there should be {} tasks, there are {}", expected, found),
+            ErrorKind::UnexpectedEnd => write!(f, "the input ends in the middle of the task")
+        }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.task == 0 {
+            write!(f, "line {}: {}", self.line, self.kind)
+        } else {
+            write!(f, "task {}, line {}: {}", self.task, self.line, self.kind)
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::VecDeque;
+
+// result of splitting cables between floor and ceiling
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Layers {
+    // `true` for cables going through the ceiling
+    Assignment(Vec<bool>),
+    // cables each crossing the next one and the last crossing the first, odd number of them
+    OddCycle(Vec<usize>)
+}
+
+// cables are pairs of distinct positions around the room, two of them cross exactly when their ends
+// interleave, so two layers are enough exactly when the crossing graph is bipartite. the graph can
+// have cables^2 edges, so it is never built: a BFS takes the cables crossing the current one out of
+// range trees, each cable once, and the layers it gives are then checked with a stack per layer
+pub fn two_layers(cables: &[(usize, usize)]) -> Layers {
+    let cables: Vec<(usize, usize)> = cables.iter().map(|(a, b)| (*a.min(b), *a.max(b))).collect();
+    let mut ranges = Ranges::new(&cables);
+
+    let mut layer: Vec<Option<bool>> = vec![None; cables.len()];
+    let mut parent: Vec<usize> = (0..cables.len()).collect();
+    let mut depth: Vec<usize> = vec![0; cables.len()];
+
+    for start in 0..cables.len() {
+        if layer[start].is_some() {
+            continue;
+        }
+        layer[start] = Some(false);
+        ranges.remove(start);
+
+        let mut queue = VecDeque::from([start]);
+        while let Some(cable) = queue.pop_front() {
+            while let Some(other) = ranges.crossing(cable) {
+                ranges.remove(other);
+                layer[other] = Some(!layer[cable].unwrap());
+                parent[other] = cable;
+                depth[other] = depth[cable] + 1;
+                queue.push_back(other);
+            }
+        }
+    }
+
+    let layer: Vec<bool> = layer.into_iter().map(|layer| layer.unwrap()).collect();
+
+    // cables of one layer must nest: going around, every cable ends while it is the last one open
+    let mut at = vec![usize::MAX; ranges.size];
+    for (cable, (first, second)) in cables.iter().enumerate() {
+        at[*first] = cable;
+        at[*second] = cable;
+    }
+
+    let mut open: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+    for (position, &cable) in at.iter().enumerate().filter(|(_, cable)| **cable != usize::MAX) {
+        let open = &mut open[layer[cable] as usize];
+        if cables[cable].0 == position {
+            open.push(cable);
+        } else if let Some(last) = open.pop().filter(|last| *last != cable) {
+            // opened inside of `cable` and still open, they cross in the same layer
+            return Layers::OddCycle(odd_cycle(cable, last, &parent, &depth));
+        }
+    }
+
+    Layers::Assignment(layer)
+}
+
+// whether every cable crosses some other one
+pub fn crossed(cables: &[(usize, usize)]) -> Vec<bool> {
+    let cables: Vec<(usize, usize)> = cables.iter().map(|(a, b)| (*a.min(b), *a.max(b))).collect();
+    let ranges = Ranges::new(&cables);
+    (0..cables.len()).map(|cable| ranges.crossing(cable).is_some()).collect()
+}
+
+// cables still in the ranges by both of their ends: a cable starting inside of another one crosses it
+// when it ends the furthest after it, one ending inside of it when it starts the furthest before it
+struct Ranges<'a> {
+    cables: &'a [(usize, usize)],
+    size: usize,
+    // (end, cable) at the start of every cable
+    by_start: Tree,
+    // (-start, cable) at the end of every cable
+    by_end: Tree
+}
+
+impl<'a> Ranges<'a> {
+    fn new(cables: &'a [(usize, usize)]) -> Self {
+        let size = cables.iter().map(|(_, second)| second + 1).max().unwrap_or(0);
+        let mut ranges = Ranges { cables, size, by_start: Tree::new(size), by_end: Tree::new(size) };
+        for (cable, (first, second)) in cables.iter().enumerate() {
+            ranges.by_start.set(*first, (*second as i64, cable));
+            ranges.by_end.set(*second, (-(*first as i64), cable));
+        }
+        ranges
+    }
+
+    fn remove(&mut self, cable: usize) {
+        let (first, second) = self.cables[cable];
+        self.by_start.set(first, Tree::EMPTY);
+        self.by_end.set(second, Tree::EMPTY);
+    }
+
+    // some cable left in the ranges that crosses `cable`
+    fn crossing(&self, cable: usize) -> Option<usize> {
+        let (first, second) = self.cables[cable];
+
+        let (end, other) = self.by_start.max(first + 1, second);
+        if end > second as i64 {
+            return Some(other);
+        }
+
+        let (start, other) = self.by_end.max(first + 1, second);
+        if start > -(first as i64) {
+            return Some(other);
+        }
+
+        None
+    }
+}
+
+// largest (value, cable) in a range of positions
+struct Tree {
+    size: usize,
+    nodes: Vec<(i64, usize)>
+}
+
+impl Tree {
+    const EMPTY: (i64, usize) = (i64::MIN, usize::MAX);
+
+    fn new(size: usize) -> Self {
+        Tree { size, nodes: vec![Self::EMPTY; 2 * size] }
+    }
+
+    fn set(&mut self, position: usize, value: (i64, usize)) {
+        let mut node = position + self.size;
+        self.nodes[node] = value;
+        while node > 1 {
+            node /= 2;
+            self.nodes[node] = self.nodes[2 * node].max(self.nodes[2 * node + 1]);
+        }
+    }
+
+    // over positions from..to, EMPTY for none
+    fn max(&self, from: usize, to: usize) -> (i64, usize) {
+        let (mut from, mut to) = (from + self.size, to + self.size);
+        let mut most = Self::EMPTY;
+        while from < to {
+            if from % 2 == 1 {
+                most = most.max(self.nodes[from]);
+                from += 1;
+            }
+            if to % 2 == 1 {
+                to -= 1;
+                most = most.max(self.nodes[to]);
+            }
+            from /= 2;
+            to /= 2;
+        }
+        most
+    }
+}
+
+// two crossing cables in the same layer of one BFS tree, joined by their paths to the common ancestor
+fn odd_cycle(a: usize, b: usize, parent: &[usize], depth: &[usize]) -> Vec<usize> {
+    let (mut a, mut b) = (a, b);
+    let mut from_a = vec![a];
+    let mut from_b = vec![b];
+
+    while a != b {
+        if depth[a] >= depth[b] {
+            a = parent[a];
+            from_a.push(a);
+        } else {
+            b = parent[b];
+            from_b.push(b);
+        }
+    }
+
+    // the common ancestor is at the end of both
+    from_b.pop();
+    from_b.reverse();
+    from_a.extend(from_b);
+    from_a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Random;
+
+    fn cross(a: (usize, usize), b: (usize, usize)) -> bool {
+        let inside = |position: usize| a.0.min(a.1) < position && position < a.0.max(a.1);
+        inside(b.0) != inside(b.1)
+    }
+
+    fn check(cables: &[(usize, usize)]) {
+        let bipartite = (0..1u32 << cables.len()).any(|ceiling| {
+            (0..cables.len()).all(|a| (a + 1..cables.len()).all(|b| {
+                (ceiling >> a & 1) != (ceiling >> b & 1) || !cross(cables[a], cables[b])
+            }))
+        });
+
+        match two_layers(cables) {
+            Layers::Assignment(ceiling) => {
+                assert!(bipartite, "{:?}", cables);
+                for a in 0..cables.len() {
+                    for b in a + 1..cables.len() {
+                        assert!(ceiling[a] != ceiling[b] || !cross(cables[a], cables[b]), "{:?}", cables);
+                    }
+                }
+            }
+            Layers::OddCycle(cycle) => {
+                assert!(!bipartite, "{:?}", cables);
+                assert_eq!(cycle.len() % 2, 1, "{:?}", cables);
+                for (index, cable) in cycle.iter().enumerate() {
+                    assert!(cross(cables[*cable], cables[cycle[(index + 1) % cycle.len()]]), "{:?}", cables);
+                }
+            }
+        }
+
+        let expected: Vec<bool> = (0..cables.len())
+            .map(|a| (0..cables.len()).any(|b| cross(cables[a], cables[b])))
+            .collect();
+        assert_eq!(crossed(cables), expected, "{:?}", cables);
+    }
+
+    #[test]
+    fn known_graphs() {
+        // nested and side by side
+        assert_eq!(two_layers(&[(0, 5), (1, 2), (3, 4)]), Layers::Assignment(vec![false; 3]));
+        // two crossing
+        assert_eq!(two_layers(&[(0, 2), (1, 3)]), Layers::Assignment(vec![false, true]));
+        // three pairwise crossing
+        let triangle = [(0, 3), (1, 4), (2, 5)];
+        match two_layers(&triangle) {
+            Layers::OddCycle(mut cycle) => {
+                cycle.sort();
+                assert_eq!(cycle, vec![0, 1, 2]);
+            }
+            layers => panic!("{:?}", layers)
+        }
+        // paths of crossings are fine
+        assert_eq!(two_layers(&[(0, 2), (1, 4), (3, 6), (5, 7)]), Layers::Assignment(vec![false, true, false, true]));
+        assert!(matches!(two_layers(&[(0, 2), (1, 4), (3, 6), (5, 8), (7, 9)]), Layers::Assignment(_)));
+        check(&[(0, 2), (1, 4), (3, 6), (5, 8), (7, 10), (9, 11)]);
+        assert!(matches!(two_layers(&[]), Layers::Assignment(_)));
+    }
+
+    #[test]
+    fn against_brute_force() {
+        let mut random = Random::new(44);
+
+        for _ in 0..2000 {
+            let mut positions: Vec<usize> = (0..2 * (1 + random.below(7))).collect();
+            for i in (1..positions.len()).rev() {
+                positions.swap(i, random.below(i + 1));
+            }
+            let cables: Vec<(usize, usize)> = positions.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+            check(&cables);
+        }
+    }
+}
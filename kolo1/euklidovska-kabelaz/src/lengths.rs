@@ -0,0 +1,222 @@
+use crate::{perimeter_position, Device};
+
+// cables of a routable rectangular room laid along the walls. every cable follows one of the two
+// stretches of wall between its devices: the one not containing a chosen origin, picked so that the
+// stretches are the shortest in total. that isn't the shortest layout, a cable between opposite walls
+// would be shorter across the room than around it. a cable runs at a distance from the walls that grows with the number of
+// cables nested inside of it, all below half a unit, so cables neither cross nor touch and devices
+// one unit apart stay apart. paths are (y, x) points in the order of `cables`
+pub fn layout(h: i32, w: i32, cables: &[(&Device, &Device)]) -> Vec<Vec<(f64, f64)>> {
+    let perimeter = 2 * (h as i64 + w as i64);
+    let positions: Vec<(i64, i64)> = cables
+        .iter()
+        .map(|(first, second)| {
+            (perimeter_position(h, w, first.y, first.x).unwrap(), perimeter_position(h, w, second.y, second.x).unwrap())
+        })
+        .collect();
+
+    // half a unit clockwise from the origin, positions measured from there
+    let origin = origin(&positions, perimeter) as f64 + 0.5;
+    let from_origin = |position: i64| (position as f64 - origin).rem_euclid(perimeter as f64);
+    let arcs: Vec<(f64, f64)> = positions
+        .iter()
+        .map(|(first, second)| {
+            let (first, second) = (from_origin(*first), from_origin(*second));
+            (first.min(second), first.max(second))
+        })
+        .collect();
+
+    let heights = heights(&arcs);
+    let most = heights.iter().copied().max().unwrap_or(0);
+    let step = 1.0 / (2.0 * (most + 1) as f64);
+
+    cables
+        .iter()
+        .zip(&positions)
+        .zip(arcs.iter().zip(&heights))
+        .map(|((cable, (first, _)), (arc, height))| {
+            // the device the arc starts at goes first
+            let (start, end) = if from_origin(*first) == arc.0 { *cable } else { (cable.1, cable.0) };
+            path(h, w, start, end, *arc, &from_origin, step * *height as f64)
+        })
+        .collect()
+}
+
+// position of a device such that going around the walls from just after it, every cable follows
+// the shorter total way: cables with the origin inside of their interval go around the other side
+// instead, which changes their length from `length` to `perimeter - length`. -1 for no such device
+fn origin(positions: &[(i64, i64)], perimeter: i64) -> i64 {
+    let mut events: Vec<(i64, i64)> = Vec::new();
+    for (first, second) in positions.iter().map(|(a, b)| (a.min(b), a.max(b))) {
+        let change = perimeter - 2 * (second - first);
+        events.push((*first, change));
+        events.push((*second, -change));
+    }
+    events.sort();
+
+    let (mut best, mut best_change, mut change) = (-1, 0, 0);
+    for (position, event) in events {
+        change += event;
+        if change < best_change {
+            best = position;
+            best_change = change;
+        }
+    }
+    best
+}
+
+// 1 for a cable with nothing inside of its arc
+fn heights(arcs: &[(f64, f64)]) -> Vec<usize> {
+    let mut ends: Vec<(f64, usize)> = Vec::new();
+    for (cable, (start, end)) in arcs.iter().enumerate() {
+        ends.push((*start, cable));
+        ends.push((*end, cable));
+    }
+    ends.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut heights = vec![1; arcs.len()];
+    let mut open: Vec<usize> = Vec::new();
+    for (_, cable) in ends {
+        if open.last() == Some(&cable) {
+            open.pop();
+            if let Some(outer) = open.last() {
+                heights[*outer] = heights[*outer].max(heights[cable] + 1);
+            }
+        } else {
+            open.push(cable);
+        }
+    }
+    heights
+}
+
+// from the first device straight off the wall to `distance`, along the walls past the corners
+// in between and back to the second device
+fn path(
+    h: i32, w: i32, first: &Device, second: &Device, arc: (f64, f64),
+    from_origin: &impl Fn(i64) -> f64, distance: f64
+) -> Vec<(f64, f64)> {
+    let (hi, wi) = (h as i64, w as i64);
+    let (h, w) = (h as f64, w as f64);
+    let off_the_wall = |y: i32, x: i32| ((y as f64).clamp(distance, h - distance), (x as f64).clamp(distance, w - distance));
+
+    let mut points = vec![(first.y as f64, first.x as f64), off_the_wall(first.y, first.x)];
+
+    // the corners clockwise from the top left one with their positions on the walls
+    let mut corners: Vec<(f64, (f64, f64))> = [
+        (0, (distance, distance)),
+        (wi, (distance, w - distance)),
+        (wi + hi, (h - distance, w - distance)),
+        (2 * wi + hi, (h - distance, distance))
+    ]
+        .into_iter()
+        .map(|(position, corner)| (from_origin(position), corner))
+        .filter(|(position, _)| arc.0 < *position && *position < arc.1)
+        .collect();
+    corners.sort_by(|a, b| a.0.total_cmp(&b.0));
+    points.extend(corners.into_iter().map(|(_, corner)| corner));
+
+    points.push(off_the_wall(second.y, second.x));
+    points.push((second.y as f64, second.x as f64));
+    points.dedup();
+    points
+}
+
+// straight-line distance between the devices, no cable between them can be shorter. it isn't the
+// length of a layout: straight cables along one wall would touch
+pub fn straight(first: &Device, second: &Device) -> f64 {
+    (second.y as f64 - first.y as f64).hypot(second.x as f64 - first.x as f64)
+}
+
+// length of a path of the layout measured along the axes
+pub fn manhattan(path: &[(f64, f64)]) -> f64 {
+    path.windows(2).map(|segment| (segment[1].0 - segment[0].0).abs() + (segment[1].1 - segment[0].1).abs()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Random;
+
+    fn device(h: i32, w: i32, position: i32, index: usize) -> Device {
+        let (y, x) = if position <= w {
+            (0, position)
+        } else if position <= w + h {
+            (position - w, w)
+        } else if position <= 2 * w + h {
+            (h, 2 * w + h - position)
+        } else {
+            (2 * (w + h) - position, 0)
+        };
+        Device { y, x, c: String::new(), index }
+    }
+
+    fn point_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+        let (dy, dx) = (b.0 - a.0, b.1 - a.1);
+        let length = dy * dy + dx * dx;
+        let t = if length == 0.0 { 0.0 } else { (((p.0 - a.0) * dy + (p.1 - a.1) * dx) / length).clamp(0.0, 1.0) };
+        (p.0 - a.0 - t * dy).hypot(p.1 - a.1 - t * dx)
+    }
+
+    fn segments_distance(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> f64 {
+        let turn = |p: (f64, f64), q: (f64, f64), r: (f64, f64)| (q.1 - p.1) * (r.0 - p.0) - (q.0 - p.0) * (r.1 - p.1);
+        if turn(a, b, c) * turn(a, b, d) < 0.0 && turn(c, d, a) * turn(c, d, b) < 0.0 {
+            return 0.0;
+        }
+        point_to_segment(a, c, d).min(point_to_segment(b, c, d)).min(point_to_segment(c, a, b)).min(point_to_segment(d, a, b))
+    }
+
+    #[test]
+    fn corner_to_corner() {
+        let (first, second) = (device(4, 4, 0, 1), device(4, 4, 8, 2));
+        let paths = layout(4, 4, &[(&first, &second)]);
+        assert_eq!(paths[0].first(), Some(&(0.0, 0.0)));
+        assert_eq!(paths[0].last(), Some(&(4.0, 4.0)));
+        assert_eq!(manhattan(&paths[0]), 8.0);
+        assert!((straight(&first, &second) - 32f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn paths_never_touch() {
+        let mut random = Random::new(49);
+
+        for _ in 0..500 {
+            let (h, w) = (1 + random.below(5) as i32, 1 + random.below(5) as i32);
+            let positions: Vec<i32> = (0..2 * (h + w)).filter(|_| random.below(3) > 0).collect();
+
+            // a random routable pairing of an even number of the positions
+            let mut cables: Vec<(usize, usize)> = Vec::new();
+            let mut open: Vec<usize> = Vec::new();
+            for index in 0..positions.len() - positions.len() % 2 {
+                let left = positions.len() - positions.len() % 2 - index;
+                if !open.is_empty() && (open.len() == left || random.below(2) == 0) {
+                    cables.push((open.pop().unwrap(), index));
+                } else {
+                    open.push(index);
+                }
+            }
+
+            let devices: Vec<Device> = positions.iter().enumerate().map(|(index, position)| device(h, w, *position, index + 1)).collect();
+            let cables: Vec<(&Device, &Device)> = cables.iter().map(|(a, b)| (&devices[*a], &devices[*b])).collect();
+            let paths = layout(h, w, &cables);
+
+            for (cable, path) in cables.iter().zip(&paths) {
+                let ends = [(cable.0.y as f64, cable.0.x as f64), (cable.1.y as f64, cable.1.x as f64)];
+                assert!(ends == [path[0], *path.last().unwrap()] || ends == [*path.last().unwrap(), path[0]]);
+                assert!(path.iter().all(|(y, x)| (0.0..=h as f64).contains(y) && (0.0..=w as f64).contains(x)));
+            }
+
+            for a in 0..paths.len() {
+                for b in a + 1..paths.len() {
+                    for first in paths[a].windows(2) {
+                        for second in paths[b].windows(2) {
+                            assert!(
+                                segments_distance(first[0], first[1], second[0], second[1]) > 1e-9,
+                                "{}x{} {:?} {:?}", h, w, paths[a], paths[b]
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
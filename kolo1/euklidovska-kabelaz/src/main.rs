@@ -1,154 +1,699 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
 use std::fs::read_to_string;
+use std::process;
+use std::str::FromStr;
+use crate::error::{ErrorKind, InputError};
+use crate::layers::{two_layers, Layers};
+use crate::matching::{fixed_pairing, maximum_matching};
+use crate::nets::{nets, Nets};
+use crate::obstacles::{place, route_among_obstacles, Place};
+use crate::polygon::{boundary_position, contains_rectangle, inside, simple_polygon, MAX_CORNERS};
+
+mod error;
+mod layers;
+mod lengths;
+mod matching;
+mod nets;
+mod obstacles;
+mod polygon;
+mod svg;
+#[cfg(test)]
+mod testing;
+
+// longest side of a room
+const MAX_SIDE: i32 = 1_000_000_000;
 
 #[derive(Clone, Debug)]
 struct Task {
+    // order in the input, from 1
+    number: usize,
     task_header: TaskHeader,
-    devices: Vec<Device>
+    devices: Vec<Device>,
+    obstacles: Vec<Obstacle>
 }
 
 #[derive(Clone, Debug)]
 struct TaskHeader {
-    h: i16,
-    w: i16,
-    n: i32
+    h: i32,
+    w: i32,
+    n: i32,
+    // number of obstacles, optional
+    k: i32,
+    // number of corners of a polygon room, 0 for rectangles
+    m: i32,
+    // corners of a polygon room clockwise, h and w are then the largest y and x
+    corners: Option<Vec<(i32, i32)>>
 }
 
 #[derive(Clone, Debug)]
 struct Device {
-    y: i16,
-    x: i16,
-    c: String
+    y: i32,
+    x: i32,
+    c: String,
+    // order in the task input, from 1
+    index: usize
+}
+
+// rectangle inside of the room that cables have to go around, given by two opposite corners
+#[derive(Clone, Debug)]
+struct Obstacle {
+    top: i32,
+    left: i32,
+    bottom: i32,
+    right: i32
+}
+
+// what to print besides the answer
+#[derive(Clone, Debug, Default)]
+struct Options {
+    // `--by-type`, list the cables grouped by connector type
+    by_type: bool,
+    // `--max`, wire as many devices as possible in rooms that aren't routable
+    max: bool,
+    // `--layers`, split the cables of rooms that aren't routable between floor and ceiling. only the
+    // cables of `fixed_pairing` are split, types with more devices could be paired differently
+    layers: bool,
+    // `--nets`, connect all devices of a type by one tree instead of in pairs
+    nets: bool,
+    // `--lengths`, measure the cables of routable rooms: the straight-line distance, which no cable can
+    // beat, and the length of one layout along the walls without touching, measured along the axes
+    lengths: bool,
+    // `--svg <task>`, print a picture of the task (numbered from 1) instead of the answers
+    svg: Option<usize>
+}
+
+// two devices connected by a cable and the pairs connected inside of the cable's loop
+#[derive(Clone, Debug)]
+struct Pair {
+    first: Device,
+    second: Device,
+    inner: Vec<Pair>
 }
 
 impl Task {
-    fn solve(&self) -> String {
-        let devices_in_correct_order = assign_devices_to_coordinates_on_side(
-            find_coordinates_on_sides(self.task_header.h, self.task_header.w),
-            &self.devices
-        );
+    fn solve(&self, options: &Options) -> String {
+        if options.nets {
+            return self.solve_nets();
+        }
+
+        if !self.on_the_walls() {
+            return self.solve_among_obstacles(options);
+        }
 
-        let mut device_route_buffer: Vec<Device> = Vec::new();
-        for device in &devices_in_correct_order {
+        let mut lines = Vec::new();
+        match self.route() {
+            Some(pairs) => {
+                lines.push("pujde to".to_owned());
+                write_pairs(&pairs, options.by_type, &mut lines);
+                if options.lengths {
+                    self.write_lengths(&pairs, &mut lines);
+                }
+            }
+            None => {
+                lines.push("ajajaj".to_owned());
+                if options.max {
+                    match self.max_route() {
+                        Ok((pairs, unconnected)) => {
+                            write_pairs(&pairs, options.by_type, &mut lines);
+                            write_unconnected(&unconnected, &mut lines);
+                        }
+                        Err(e) => lines.push(format!("Err: {}", e))
+                    }
+                }
+                if options.layers {
+                    self.write_layers(&mut lines);
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    // no obstacles and every device on the walls
+    fn on_the_walls(&self) -> bool {
+        self.obstacles.is_empty() && self.devices.iter().all(|device| {
+            self.task_header.wall_position(device.y, device.x).is_some()
+        })
+    }
+
+    // rooms with obstacles or devices off the walls, cables are listed in input order of their first device
+    fn solve_among_obstacles(&self, options: &Options) -> String {
+        for device in &self.devices {
+            if let Place::Inside(obstacle) = place(self, device) {
+                eprintln!(
+                    "task {}: device {} ({}, {}) is inside of obstacle {}, no cable can get to it",
+                    self.number, device.index, device.y, device.x, obstacle + 1
+                );
+            }
+        }
+
+        let mut lines = Vec::new();
+        match route_among_obstacles(self) {
+            Ok(Some(cables)) => {
+                lines.push("pujde to".to_owned());
+                let pairs: Vec<Pair> = cables
+                    .into_iter()
+                    .map(|(first, second)| Pair { first: self.devices[first].clone(), second: self.devices[second].clone(), inner: Vec::new() })
+                    .collect();
+                write_pairs(&pairs, options.by_type, &mut lines);
+                if options.lengths {
+                    lines.push("Err: --lengths only works in rectangular rooms without obstacles and with every device on the walls".to_owned());
+                }
+            }
+            Ok(None) => {
+                lines.push("ajajaj".to_owned());
+                if options.max || options.layers {
+                    lines.push("Err: --max and --layers only work in rooms without obstacles and with every device on the walls".to_owned());
+                }
+            }
+            Err(e) => {
+                lines.push("nevim".to_owned());
+                lines.push(format!("Err: {}", e));
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    // every type is one net, its devices listed going around the room on one line
+    fn solve_nets(&self) -> String {
+        if !self.on_the_walls() {
+            return "nevim\nErr: --nets only works in rooms without obstacles and with every device on the walls".to_owned();
+        }
+
+        let devices_in_correct_order = devices_around_the_room(&self.task_header, &self.devices);
+        let types: Vec<&str> = devices_in_correct_order.iter().map(|device| device.c.as_str()).collect();
+        let device_to_string = |position: &usize| {
+            let device = devices_in_correct_order[*position];
+            format!("{} ({}, {})", device.index, device.y, device.x)
+        };
+
+        let mut lines = Vec::new();
+        match nets(&types) {
+            Nets::Trees(trees) => {
+                lines.push("pujde to".to_owned());
+                for tree in trees {
+                    let devices: Vec<String> = tree.iter().map(device_to_string).collect();
+                    lines.push(format!("  {} {}", devices.join(" - "), types[tree[0]]));
+                }
+            }
+            Nets::Crossing(positions) => {
+                lines.push("ajajaj".to_owned());
+                let devices: Vec<String> = positions.iter().map(device_to_string).collect();
+                lines.push(format!("nets {} and {} cross: {}", types[positions[0]], types[positions[1]], devices.join(", ")));
+            }
+            Nets::Alone(position) => {
+                lines.push("ajajaj".to_owned());
+                lines.push(format!("net {} has only one device: {}", types[position], device_to_string(&position)));
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    // length of every cable and of all of them, the straight-line lower bound and laid along the walls.
+    // the layout isn't the shortest one, a cable between opposite walls goes around instead of across
+    fn write_lengths(&self, pairs: &[Pair], lines: &mut Vec<String>) {
+        if self.task_header.corners.is_some() {
+            lines.push("Err: --lengths only works in rectangular rooms without obstacles and with every device on the walls".to_owned());
+            return;
+        }
+
+        let cables: Vec<(&Device, &Device)> = flatten(pairs).into_iter().map(|(pair, _)| (&pair.first, &pair.second)).collect();
+        let paths = lengths::layout(self.task_header.h, self.task_header.w, &cables);
+        let (mut straight, mut along_the_walls) = (0.0, 0.0);
+        lines.push("lengths:".to_owned());
+        for ((pair, _), path) in flatten(pairs).into_iter().zip(&paths) {
+            let (pair_straight, pair_along_the_walls) = (lengths::straight(&pair.first, &pair.second), lengths::manhattan(path));
+            lines.push(format!(
+                "  {} {}: straight-line distance (lower bound) {:.3}, along the walls {:.3}",
+                pair_to_string(pair), pair.first.c, pair_straight, pair_along_the_walls
+            ));
+            straight += pair_straight;
+            along_the_walls += pair_along_the_walls;
+        }
+        lines.push(format!("total: straight-line distance (lower bound) {:.3}, along the walls {:.3}", straight, along_the_walls));
+    }
+
+    // largest set of pairs that can be cabled without crossings, and the devices left out in input order
+    fn max_route(&self) -> Result<(Vec<Pair>, Vec<&Device>), String> {
+        let devices_in_correct_order = devices_around_the_room(&self.task_header, &self.devices);
+        let types: Vec<&str> = devices_in_correct_order.iter().map(|device| device.c.as_str()).collect();
+        let partner = maximum_matching(&types)?;
+
+        let unconnected = self.unconnected(&devices_in_correct_order, &partner);
+        Ok((nest(&devices_in_correct_order, &partner), unconnected))
+    }
+
+    // devices without a partner in input order, `partner` is indexed like `devices_in_correct_order`
+    fn unconnected(&self, devices_in_correct_order: &[&Device], partner: &[Option<usize>]) -> Vec<&Device> {
+        // devices are told apart by their input index
+        let mut connected = vec![false; self.devices.len() + 1];
+        for (device, partner) in devices_in_correct_order.iter().zip(partner) {
+            connected[device.index] = partner.is_some();
+        }
+        self.devices.iter().filter(|device| !connected[device.index]).collect()
+    }
+
+    // cables of the fixed pairing with their layers, or cables crossing in an odd cycle. with more
+    // than two devices of a type another pairing might still fit into two layers when this one doesn't
+    fn write_layers(&self, lines: &mut Vec<String>) {
+        let devices_in_correct_order = devices_around_the_room(&self.task_header, &self.devices);
+        let types: Vec<&str> = devices_in_correct_order.iter().map(|device| device.c.as_str()).collect();
+        let partner = fixed_pairing(&types);
+
+        let cables: Vec<(usize, usize)> = partner
+            .iter()
+            .enumerate()
+            .filter_map(|(position, partner)| partner.filter(|partner| *partner > position).map(|partner| (position, partner)))
+            .collect();
+        let cable_to_string = |(first, second): (usize, usize)| {
+            let (first, second) = (devices_in_correct_order[first], devices_in_correct_order[second]);
+            format!("{} {}", pair_to_string(&Pair { first: first.clone(), second: second.clone(), inner: Vec::new() }), first.c)
+        };
+
+        match two_layers(&cables) {
+            Layers::Assignment(ceiling) => {
+                lines.push("2 layers:".to_owned());
+                for (cable, ceiling) in cables.iter().zip(ceiling) {
+                    lines.push(format!("  {} {}", cable_to_string(*cable), if ceiling { "ceiling" } else { "floor" }));
+                }
+            }
+            Layers::OddCycle(cycle) => {
+                lines.push(format!("2 layers aren't enough for the fixed pairing, {} cables cross in a cycle:", cycle.len()));
+                for cable in cycle {
+                    lines.push(format!("  {}", cable_to_string(cables[cable])));
+                }
+            }
+        }
+
+        write_unconnected(&self.unconnected(&devices_in_correct_order, &partner), lines);
+    }
+
+
+    // pairs the devices going around the room, None if some cables would have to cross
+    fn route(&self) -> Option<Vec<Pair>> {
+        let devices_in_correct_order = devices_around_the_room(&self.task_header, &self.devices);
+
+        // open devices with the pairs closed since they were opened
+        let mut device_route_buffer: Vec<(Device, Vec<Pair>)> = Vec::new();
+        let mut pairs: Vec<Pair> = Vec::new();
+        for device in devices_in_correct_order {
 
             match device_route_buffer.last() {
-                None => device_route_buffer.push(device.clone()),
-                Some(last_device) => {
-                    if last_device.c == device.c {
-                        device_route_buffer.pop();
-                    } else {
-                        device_route_buffer.push(device.clone());
+                Some((last_device, _)) if last_device.c == device.c => {
+                    let (first, inner) = device_route_buffer.pop().unwrap();
+                    let pair = Pair { first, second: device.clone(), inner };
+
+                    match device_route_buffer.last_mut() {
+                        Some((_, outer)) => outer.push(pair),
+                        None => pairs.push(pair)
                     }
                 }
+                _ => device_route_buffer.push((device.clone(), Vec::new()))
             }
         }
 
         if device_route_buffer.is_empty() {
-            return "pujde to".to_owned();
+            return Some(pairs);
         }
-        "ajajaj".to_owned()
+        None
     }
 }
 
-impl TaskHeader {
-    fn new(line: &str) -> Self {
+// pairs of a non-crossing matching, `partner[i]` is the position of the device paired with device `i`
+fn nest(devices: &[&Device], partner: &[Option<usize>]) -> Vec<Pair> {
+    let mut open: Vec<(usize, Vec<Pair>)> = Vec::new();
+    let mut pairs: Vec<Pair> = Vec::new();
 
-        let numbers = line.split_whitespace();
+    for (position, device) in devices.iter().enumerate() {
+        let partner = match partner[position] {
+            Some(partner) => partner,
+            None => continue
+        };
 
-        let mut h: i16 = 0;
-        let mut w: i16 = 0;
-        let mut n: i32 = 0;
+        if partner > position {
+            open.push((position, Vec::new()));
+            continue;
+        }
 
-        for (index, number) in numbers.enumerate() {
-            match index {
-                0 => h = number.parse::<i16>().unwrap(),
-                1 => w = number.parse::<i16>().unwrap(),
-                2 => n = number.parse::<i32>().unwrap(),
-                _ => panic!()
-            }
+        let (first, inner) = open.pop().unwrap();
+        assert_eq!(first, partner, "cables cross");
+        let pair = Pair { first: devices[first].clone(), second: (*device).clone(), inner };
+        match open.last_mut() {
+            Some((_, outer)) => outer.push(pair),
+            None => pairs.push(pair)
         }
+    }
+
+    pairs
+}
+
+// `1 (3, 4) - 2 (3, 3)`, devices by input index and (y, x)
+fn pair_to_string(pair: &Pair) -> String {
+    format!(
+        "{} ({}, {}) - {} ({}, {})",
+        pair.first.index, pair.first.y, pair.first.x,
+        pair.second.index, pair.second.y, pair.second.x
+    )
+}
 
-        if !(1..=10000).contains(&h) {
-            panic!("\"h\" is less than 1 or more than 10000")
+fn write_unconnected(devices: &[&Device], lines: &mut Vec<String>) {
+    lines.push(format!("unconnected: {}", devices.len()));
+    for device in devices {
+        lines.push(format!("  {} ({}, {}) {}", device.index, device.y, device.x, device.c));
+    }
+}
+
+// pairs in nesting order, or flat lists under each connector type
+fn write_pairs(pairs: &[Pair], by_type: bool, lines: &mut Vec<String>) {
+    if by_type {
+        let mut groups: BTreeMap<&str, Vec<&Pair>> = BTreeMap::new();
+        for (pair, _) in flatten(pairs) {
+            groups.entry(pair.first.c.as_str()).or_default().push(pair);
         }
 
-        if !(1..=10000).contains(&w) {
-            panic!("\"w\" is less than 1 or more than 10000")
+        for (c, pairs) in groups {
+            lines.push(format!("{}:", c));
+            for pair in pairs {
+                lines.push(format!("  {}", pair_to_string(pair)));
+            }
         }
+    } else {
+        write_nested(pairs, lines);
+    }
+}
 
-        if !(1..=40000).contains(&n) {
-            panic!("\"n\" is less than 1 or more than 10000")
+// one pair per line, the pairs inside of a cable's loop right after it and one level deeper. the level
+// is a field rather than indentation, rooms can nest thousands of cables deep
+fn write_nested(pairs: &[Pair], lines: &mut Vec<String>) {
+    for (pair, depth) in flatten(pairs) {
+        lines.push(format!("  {} {}, depth {}", pair_to_string(pair), pair.first.c, depth));
+    }
+}
+
+// every pair with how many cables' loops it is inside of, each pair followed by the pairs inside of it
+fn flatten(pairs: &[Pair]) -> Vec<(&Pair, usize)> {
+    let mut flat = Vec::new();
+    let mut stack: Vec<std::slice::Iter<Pair>> = vec![pairs.iter()];
+    while let Some(level) = stack.last_mut() {
+        match level.next() {
+            Some(pair) => {
+                flat.push((pair, stack.len() - 1));
+                stack.push(pair.inner.iter());
+            }
+            None => {
+                stack.pop();
+            }
         }
+    }
+    flat
+}
 
-        Self { h, w, n }
+impl TaskHeader {
+    fn new(line: &str) -> Result<Self, ErrorKind> {
+
+        // `polygon m n k`, the m corners follow on their own lines
+        if let Some(numbers) = line.strip_prefix("polygon ") {
+            return Self::polygon(numbers);
+        }
+
+        let mut numbers = line.split_whitespace();
+
+        let h: i32 = number("h", numbers.next())?;
+        let w: i32 = number("w", numbers.next())?;
+        let n: i32 = number("n", numbers.next())?;
+        let k: i32 = optional_number("k", numbers.next())?;
+        end_of_line(numbers)?;
+
+        in_range("h", h, 1, MAX_SIDE)?;
+        in_range("w", w, 1, MAX_SIDE)?;
+        in_range("n", n, 1, 40000)?;
+        in_range("k", k, 0, 1000)?;
+
+        Ok(Self { h, w, n, k, m: 0, corners: None })
+    }
+
+    fn polygon(line: &str) -> Result<Self, ErrorKind> {
+
+        let mut numbers = line.split_whitespace();
+
+        let m: i32 = number("m", numbers.next())?;
+        let n: i32 = number("n", numbers.next())?;
+        let k: i32 = optional_number("k", numbers.next())?;
+        end_of_line(numbers)?;
+
+        in_range("m", m, 3, MAX_CORNERS as i32)?;
+        in_range("n", n, 1, 40000)?;
+        in_range("k", k, 0, 1000)?;
+
+        Ok(Self { h: 0, w: 0, n, k, m, corners: Some(Vec::new()) })
+    }
+
+    // sets the corners of a polygon room once they are all read
+    fn set_corners(&mut self, corners: &[(i32, i32)]) -> Result<(), ErrorKind> {
+        let corners = simple_polygon(corners)?;
+        self.h = corners.iter().map(|(y, _)| *y).max().unwrap();
+        self.w = corners.iter().map(|(_, x)| *x).max().unwrap();
+        self.corners = Some(corners);
+        Ok(())
+    }
+
+    // distance along the walls clockwise, None for places off the walls
+    fn wall_position(&self, y: i32, x: i32) -> Option<i64> {
+        match &self.corners {
+            Some(corners) => boundary_position(corners, y, x),
+            None => perimeter_position(self.h, self.w, y, x)
+        }
     }
 }
 
+// `y x` of a corner of a polygon room
+fn corner(line: &str) -> Result<(i32, i32), ErrorKind> {
+    let mut numbers = line.split_whitespace();
+    let y: i32 = number("y", numbers.next())?;
+    let x: i32 = number("x", numbers.next())?;
+    end_of_line(numbers)?;
+    Ok((y, x))
+}
+
 impl Device {
-    fn new(task: &TaskHeader, line: &str) -> Self {
+    fn new(task: &TaskHeader, line: &str, index: usize) -> Result<Self, ErrorKind> {
+
+        let mut numbers = line.split_whitespace();
 
-        let numbers = line.split_whitespace();
+        let y: i32 = number("y", numbers.next())?;
+        let x: i32 = number("x", numbers.next())?;
+        let c = numbers.next().ok_or(ErrorKind::Missing("c"))?.to_owned();
+        end_of_line(numbers)?;
 
-        let mut y: i16 = 0;
-        let mut x: i16 = 0;
-        let mut c: String = String::new();
+        in_range("y", y, 0, task.h)?;
+        in_range("x", x, 0, task.w)?;
 
-        for (index, number) in numbers.enumerate() {
-            match index {
-                0 => y = number.parse::<i16>().unwrap(),
-                1 => x = number.parse::<i16>().unwrap(),
-                2 => c = number.to_owned(),
-                _ => panic!()
+        if let Some(corners) = &task.corners {
+            if boundary_position(corners, y, x).is_none() && !inside(corners, y, x) {
+                return Err(ErrorKind::OutsideOfTheRoom { y, x });
             }
         }
 
-        if y < 0 || y > task.h {
-            panic!("\"y\" is less than 0 or more than \"h\"")
+        Ok(Self { y, x, c, index })
+    }
+}
+
+impl Obstacle {
+    fn new(task: &TaskHeader, line: &str) -> Result<Self, ErrorKind> {
+
+        let mut numbers = line.split_whitespace();
+
+        let y1: i32 = number("y1", numbers.next())?;
+        let x1: i32 = number("x1", numbers.next())?;
+        let y2: i32 = number("y2", numbers.next())?;
+        let x2: i32 = number("x2", numbers.next())?;
+        end_of_line(numbers)?;
+
+        let (top, bottom) = (y1.min(y2), y1.max(y2));
+        let (left, right) = (x1.min(x2), x1.max(x2));
+
+        // cables have to be able to go around on every side
+        if top <= 0 || bottom >= task.h || left <= 0 || right >= task.w {
+            return Err(ErrorKind::ObstacleTouchesTheWalls);
         }
 
-        if x < 0 || x > task.w {
-            panic!("\"y\" is less than 0 or more than \"h\"")
+        if let Some(corners) = &task.corners {
+            if !contains_rectangle(corners, top, left, bottom, right) {
+                return Err(ErrorKind::ObstacleTouchesTheWalls);
+            }
         }
 
+        if top == bottom || left == right {
+            return Err(ErrorKind::ObstacleWithoutArea);
+        }
+
+        Ok(Self { top, left, bottom, right })
+    }
 
-        Self { y, x, c }
+    fn touches(&self, other: &Obstacle) -> bool {
+        self.top <= other.bottom && other.top <= self.bottom && self.left <= other.right && other.left <= self.right
     }
 }
 
-fn main() {
-    let mut lines = Vec::new();
+fn number<T: FromStr>(what: &'static str, text: Option<&str>) -> Result<T, ErrorKind> {
+    let text = text.ok_or(ErrorKind::Missing(what))?;
+    text.parse().map_err(|_| ErrorKind::NotANumber { what, text: text.to_owned() })
+}
+
+// 0 when the line ends before it
+fn optional_number(what: &'static str, text: Option<&str>) -> Result<i32, ErrorKind> {
+    match text {
+        Some(text) => number(what, Some(text)),
+        None => Ok(0)
+    }
+}
+
+fn end_of_line<'a>(mut numbers: impl Iterator<Item = &'a str>) -> Result<(), ErrorKind> {
+    match numbers.next() {
+        Some(_) => Err(ErrorKind::TooManyValues),
+        None => Ok(())
+    }
+}
 
-    for line in read_to_string("input.txt").unwrap().lines() {
-        lines.push(line.to_string())
+fn in_range(what: &'static str, value: i32, min: i32, max: i32) -> Result<(), ErrorKind> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(ErrorKind::OutOfRange { what, value: value as i64, min: min as i64, max: max as i64 })
     }
+}
 
+// the tasks of the whole input, the first line is their number
+fn parse(input: &str) -> Result<Vec<Task>, InputError> {
     let mut ports = 0;
+    let mut obstacles = 0;
+    let mut corners: Vec<(i32, i32)> = Vec::new();
+    // devices of the current task by their place
+    let mut places: HashMap<(i32, i32), usize> = HashMap::new();
     let mut tasks: Vec<Task> = Vec::new();
-    let mut current_task: Task = Task { task_header: TaskHeader { h: 0, w: 0, n: 0, }, devices: vec![] };
-    for (index, line) in lines.iter().enumerate() {
+    let mut expected = 0;
+    let mut current_task: Task = Task {
+        number: 0, task_header: TaskHeader { h: 0, w: 0, n: 0, k: 0, m: 0, corners: None }, devices: vec![], obstacles: vec![]
+    };
+
+    for (index, line) in input.lines().enumerate() {
+        let error = |task: usize| move |kind: ErrorKind| InputError { task, line: index + 1, kind };
+
         if index == 0 {
+            let mut numbers = line.split_whitespace();
+            expected = number("tasks", numbers.next()).map_err(error(0))?;
+            end_of_line(numbers).map_err(error(0))?;
             continue
         }
 
-        if ports == 0 {
-            let new_task_header = TaskHeader::new(line);
-            current_task = Task { task_header: new_task_header.clone(), devices: vec![] };
+        if ports == 0 && obstacles == 0 {
+            let number = tasks.len() + 1;
+            let new_task_header = TaskHeader::new(line).map_err(error(number))?;
             ports = new_task_header.n;
-        } else {
-            let device = Device::new(&current_task.task_header, line);
+            obstacles = new_task_header.k;
+            current_task = Task { number, task_header: new_task_header, devices: vec![], obstacles: vec![] };
+            corners.clear();
+            places.clear();
+            continue
+        }
+
+        let error = error(current_task.number);
+
+        // corners of a polygon room first, then devices and obstacles
+        if corners.len() < current_task.task_header.m as usize {
+            corners.push(corner(line).map_err(error)?);
+            if corners.len() == current_task.task_header.m as usize {
+                current_task.task_header.set_corners(&corners).map_err(error)?;
+            }
+            continue
+        }
+
+        if ports > 0 {
+            let device = Device::new(&current_task.task_header, line, current_task.devices.len() + 1).map_err(error)?;
+            if let Some(first) = places.insert((device.y, device.x), device.index) {
+                return Err(error(ErrorKind::DuplicateDevice { first, second: device.index, y: device.y, x: device.x }));
+            }
             current_task.devices.push(device);
             ports -= 1;
+        } else {
+            let obstacle = Obstacle::new(&current_task.task_header, line).map_err(error)?;
+            for (other_index, other) in current_task.obstacles.iter().enumerate() {
+                if obstacle.touches(other) {
+                    return Err(error(ErrorKind::ObstaclesTouch(other_index + 1, current_task.obstacles.len() + 1)));
+                }
+            }
+            current_task.obstacles.push(obstacle);
+            obstacles -= 1;
+        }
+
+        // save task
+        if ports == 0 && obstacles == 0 {
+            tasks.push(current_task.clone())
+        }
+    }
 
-            // save task
-            if ports == 0 {
-                tasks.push(current_task.clone())
+    let lines = input.lines().count();
+    if ports > 0 || obstacles > 0 || corners.len() < current_task.task_header.m as usize {
+        return Err(InputError { task: current_task.number, line: lines + 1, kind: ErrorKind::UnexpectedEnd });
+    }
+
+    if tasks.len() != expected {
+        return Err(InputError { task: 0, line: 1, kind: ErrorKind::TaskCount { expected, found: tasks.len() } });
+    }
+
+    Ok(tasks)
+}
+
+fn main() {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--by-type" => options.by_type = true,
+            "--max" => options.max = true,
+            "--layers" => options.layers = true,
+            "--nets" => options.nets = true,
+            "--lengths" => options.lengths = true,
+            "--svg" => match args.next().and_then(|task| task.parse().ok()) {
+                Some(task) => options.svg = Some(task),
+                None => {
+                    eprintln!("--svg needs a task number");
+                    process::exit(1);
+                }
+            },
+            _ => {
+                eprintln!("unknown argument \"{}\"", arg);
+                process::exit(1);
             }
         }
     }
 
+    let tasks = match parse(&read_to_string("input.txt").unwrap()) {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            eprintln!("input.txt: {}", e);
+            process::exit(1);
+        }
+    };
+
+    if let Some(task) = options.svg {
+        match task.checked_sub(1).and_then(|index| tasks.get(index)) {
+            Some(task) => println!("{}", svg::render(task)),
+            None => {
+                eprintln!("--svg: task {} doesn't exist, there are {}", task, tasks.len());
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
     // solve tasks
     let mut answers: Vec<String> = Vec::new();
     for task in tasks {
-        answers.push(task.solve());
+        answers.push(task.solve(&options));
     }
 
     for answer in answers {
@@ -156,56 +701,83 @@ fn main() {
     }
 }
 
-// returns devices in correct order around the room
-fn assign_devices_to_coordinates_on_side(coords_on_side: Vec<(i16, i16)>, devices: &Vec<Device>) -> Vec<Device> {
-    let mut devices_in_order: Vec<Device> = Vec::new();
+// returns devices in correct order around the room, devices at the same place in input order
+fn devices_around_the_room<'a>(task: &TaskHeader, devices: &'a [Device]) -> Vec<&'a Device> {
+    let mut devices_in_order: Vec<(i64, &Device)> = devices
+        .iter()
+        .filter_map(|device| Some((task.wall_position(device.y, device.x)?, device)))
+        .collect();
 
-    for (y, x) in coords_on_side {
-        for device in devices {
-            if device.y == y && device.x == x {
-                devices_in_order.push(device.clone());
-            }
-        }
-    }
+    devices_in_order.sort_by_key(|(position, _)| *position);
+    devices_in_order.into_iter().map(|(_, device)| device).collect()
+}
 
-    devices_in_order
+// clockwise distance along the walls from (0, 0), None for places off the walls.
+// every corner belongs to the side it ends: top, then right, bottom and left
+fn perimeter_position(h: i32, w: i32, y: i32, x: i32) -> Option<i64> {
+    let (h, w, y, x) = (h as i64, w as i64, y as i64, x as i64);
+
+    if y == 0 {
+        // TOP SIDE
+        Some(x)
+    } else if x == w {
+        // RIGHT SIDE
+        Some(w + y)
+    } else if y == h {
+        // BOTTOM SIDE
+        Some(w + h + (w - x))
+    } else if x == 0 {
+        // LEFT SIDE
+        Some(2 * w + h + (h - y))
+    } else {
+        None
+    }
 }
 
-//                                                    y    x
-fn find_coordinates_on_sides(h: i16, w: i16) -> Vec<(i16, i16)> {
-    let mut coordinates = Vec::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // TOP SIDE
-    for i in 0..w+1 {
-        coordinates.push((0, i))
+    fn error(input: &str) -> (usize, usize, ErrorKind) {
+        let e = parse(input).unwrap_err();
+        (e.task, e.line, e.kind)
     }
 
-    // RIGHT SIDE
-    for i in 0..h+1 {
-        coordinates.push((i, w))
+    #[test]
+    fn numbers_and_lines() {
+        assert_eq!(error("x\n"), (0, 1, ErrorKind::NotANumber { what: "tasks", text: "x".to_owned() }));
+        assert_eq!(error("1\n4 4\n"), (1, 2, ErrorKind::Missing("n")));
+        assert_eq!(error("1\n4 4 1 0 7\n"), (1, 2, ErrorKind::TooManyValues));
+        assert_eq!(error("1\n0 4 1\n"), (1, 2, ErrorKind::OutOfRange { what: "h", value: 0, min: 1, max: MAX_SIDE as i64 }));
+        assert_eq!(error("2\n4 4 2\n0 1 A\n0 2 A\n"), (0, 1, ErrorKind::TaskCount { expected: 2, found: 1 }));
+        assert_eq!(error("1\n4 4 2\n0 1 A\n"), (1, 4, ErrorKind::UnexpectedEnd));
     }
 
-    // BOTTOM SIDE
-    let mut bottom = Vec::new();
-    for i in 0..w+1 {
-        bottom.push((h, i))
-    }
-    bottom.reverse();
-    for device in bottom {
-        coordinates.push(device)
+    #[test]
+    fn devices_and_obstacles() {
+        assert_eq!(error("1\n4 4 2\n0 1 A\n0 1 A\n"), (1, 4, ErrorKind::DuplicateDevice { first: 1, second: 2, y: 0, x: 1 }));
+        assert_eq!(error("1\n4 4 2 1\n0 1 A\n0 2 A\n0 1 2 2\n"), (1, 5, ErrorKind::ObstacleTouchesTheWalls));
+        assert_eq!(error("1\n4 4 2 1\n0 1 A\n0 2 A\n1 1 1 3\n"), (1, 5, ErrorKind::ObstacleWithoutArea));
+        assert_eq!(error("1\n9 9 2 2\n0 1 A\n0 2 A\n1 1 3 3\n3 3 5 5\n"), (1, 6, ErrorKind::ObstaclesTouch(1, 2)));
     }
 
-    // LEFT SIDE
-    let mut left = Vec::new();
-    for i in 0..h+1 {
-        left.push((i, 0))
-    }
-    left.reverse();
-    for device in left {
-        coordinates.push(device)
+    #[test]
+    fn polygon_rooms() {
+        assert_eq!(error("1\npolygon 3 1\n0 0\n0 4\n4 0\n3 3 A\n"), (1, 6, ErrorKind::OutsideOfTheRoom { y: 3, x: 3 }));
+        assert_eq!(error("1\npolygon 3 1\n0 0\n0 0\n4 4\n0 0 A\n"), (1, 5, ErrorKind::EdgeWithoutLength(1)));
+        assert_eq!(error("1\npolygon 4 1\n0 0\n0 4\n4 0\n4 4\n0 0 A\n"), (1, 6, ErrorKind::EdgesCross(2, 4)));
+        // edges that only meet their neighbours always enclose some area, so parse never gets to RoomWithoutArea
+        assert_eq!(ErrorKind::RoomWithoutArea.to_string(), "the room has no area");
+
+        let tasks = parse("1\npolygon 4 2\n0 0\n4 0\n4 4\n0 4\n0 1 A\n4 3 A\n").unwrap();
+        assert_eq!(tasks[0].task_header.corners, Some(vec![(0, 0), (0, 4), (4, 4), (4, 0)]));
+        assert_eq!((tasks[0].task_header.h, tasks[0].task_header.w), (4, 4));
     }
 
-    coordinates.dedup();
-    coordinates.pop();
-    coordinates
+    #[test]
+    fn where_errors_are() {
+        let e = parse("1\n4 4 2\n0 1 A\n0 5 A\n").unwrap_err();
+        assert_eq!(e.to_string(), "task 1, line 4: \"x\" is 5, it has to be from 0 to 4");
+        assert_eq!(parse("1 2\n").unwrap_err().to_string(), "line 1: there is more on the line than expected");
+    }
 }
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+// most devices the interval DP takes after neighbours are paired. it needs devices^2 memory and
+// up to devices^3 / 12 steps, reached with two types alternating: 1000 devices take a few tenths
+// of a second, twice as many ten times as long
+pub const MAX_DEVICES: usize = 1000;
+
+// partner of every device (by position in `types`, going around the room) in a largest set of
+// same-type pairs that can be cabled without crossings
+pub fn maximum_matching(types: &[&str]) -> Result<Vec<Option<usize>>, String> {
+    let types = type_ids(types.iter().copied());
+
+    // neighbours of the same type are always worth pairing: any largest matching can be changed
+    // to contain them without losing a pair, so only what is left needs the DP
+    let mut partner = vec![None; types.len()];
+    let left = pair_neighbours(&(0..types.len()).collect::<Vec<usize>>(), &types, &mut partner);
+
+    if left.len() > MAX_DEVICES {
+        return Err(format!(
+            "{} devices are left after pairing neighbours, at most {} can be matched",
+            left.len(), MAX_DEVICES
+        ));
+    }
+
+    for (a, b) in interval_dp(&left.iter().map(|position| types[*position]).collect::<Vec<usize>>()) {
+        partner[left[a]] = Some(left[b]);
+        partner[left[b]] = Some(left[a]);
+    }
+
+    Ok(partner)
+}
+
+// pairing of every device that has a partner of its type: neighbours first like in a routable room,
+// what is left is paired by type in the order around the room (first with second, third with fourth..)
+pub fn fixed_pairing(types: &[&str]) -> Vec<Option<usize>> {
+    let types = type_ids(types.iter().copied());
+    let mut partner = vec![None; types.len()];
+    let left = pair_neighbours(&(0..types.len()).collect::<Vec<usize>>(), &types, &mut partner);
+
+    let mut waiting: HashMap<usize, usize> = HashMap::new();
+    for position in left {
+        if let Some(first) = waiting.remove(&types[position]) {
+            partner[first] = Some(position);
+            partner[position] = Some(first);
+        } else {
+            waiting.insert(types[position], position);
+        }
+    }
+
+    partner
+}
+
+// ids 0.. of the types in the order they first show up
+pub fn type_ids<'a>(types: impl IntoIterator<Item = &'a str>) -> Vec<usize> {
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    types.into_iter().map(|c| {
+        let next_id = ids.len();
+        *ids.entry(c).or_insert(next_id)
+    }).collect()
+}
+
+// pairs neighbours of the same type along `sequence` (of ids into `types` and `partner`) until there
+// are none, returns what is left in order
+pub fn pair_neighbours(sequence: &[usize], types: &[usize], partner: &mut [Option<usize>]) -> Vec<usize> {
+    let mut left: Vec<usize> = Vec::new();
+
+    for &item in sequence {
+        match left.last() {
+            Some(&last) if types[last] == types[item] => {
+                partner[last] = Some(item);
+                partner[item] = Some(last);
+                left.pop();
+            }
+            _ => left.push(item)
+        }
+    }
+
+    left
+}
+
+// largest non-crossing matching of a sequence, pairs of positions.
+// best[i][j] is the most pairs within i..j, i is either left alone or paired with some k of its type.
+// O(m^3) time, every k of the type of i is tried for every interval
+fn interval_dp(types: &[usize]) -> Vec<(usize, usize)> {
+    let m = types.len();
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+
+    // next position with the same type
+    let mut next_same = vec![usize::MAX; m];
+    let mut last_seen: HashMap<usize, usize> = HashMap::new();
+    for position in (0..m).rev() {
+        if let Some(next) = last_seen.insert(types[position], position) {
+            next_same[position] = next;
+        }
+    }
+
+    let mut best: Vec<u16> = vec![0; (m + 1) * (m + 1)];
+    for i in (0..m).rev() {
+        for j in i + 1..=m {
+            let mut most = best[at(i + 1, j)];
+            let mut k = next_same[i];
+            while k < j {
+                most = most.max(1 + best[at(i + 1, k)] + best[at(k + 1, j)]);
+                k = next_same[k];
+            }
+            best[at(i, j)] = most;
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let mut intervals = vec![(0, m)];
+    while let Some((i, j)) = intervals.pop() {
+        if j < i + 2 {
+            continue;
+        }
+        if best[at(i, j)] == best[at(i + 1, j)] {
+            intervals.push((i + 1, j));
+            continue;
+        }
+
+        let mut k = next_same[i];
+        while 1 + best[at(i + 1, k)] + best[at(k + 1, j)] != best[at(i, j)] {
+            k = next_same[k];
+        }
+        pairs.push((i, k));
+        intervals.push((i + 1, k));
+        intervals.push((k + 1, j));
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Random;
+
+    // most pairs by trying every partner of the first device, or none
+    fn brute_force(types: &[&str]) -> usize {
+        match types.split_first() {
+            None => 0,
+            Some((first, rest)) => {
+                let mut most = brute_force(rest);
+                for (k, c) in rest.iter().enumerate() {
+                    if c == first {
+                        most = most.max(1 + brute_force(&rest[..k]) + brute_force(&rest[k + 1..]));
+                    }
+                }
+                most
+            }
+        }
+    }
+
+    fn check(types: &[&str], partner: &[Option<usize>]) -> usize {
+        let mut open: Vec<usize> = Vec::new();
+        for (position, other) in partner.iter().enumerate() {
+            let other = match other {
+                Some(other) => *other,
+                None => continue
+            };
+            assert_eq!(partner[other], Some(position), "{:?}", types);
+            assert_eq!(types[position], types[other], "{:?}", types);
+            if other > position {
+                open.push(position);
+            } else {
+                assert_eq!(open.pop(), Some(other), "{:?} cables cross", types);
+            }
+        }
+        partner.iter().filter(|partner| partner.is_some()).count() / 2
+    }
+
+    #[test]
+    fn against_brute_force() {
+        let mut random = Random::new(43);
+
+        for _ in 0..3000 {
+            let kinds = 1 + random.below(4);
+            let types: Vec<&str> = (0..random.below(13)).map(|_| ["A", "B", "C", "D"][random.below(kinds)]).collect();
+            let partner = maximum_matching(&types).unwrap();
+            assert_eq!(check(&types, &partner), brute_force(&types), "{:?}", types);
+        }
+    }
+
+    #[test]
+    fn fixed_pairs() {
+        // neighbours first, the two Bs at the end before the first one, then by type in order
+        let types = ["A", "B", "A", "C", "C", "B", "B"];
+        assert_eq!(
+            fixed_pairing(&types),
+            vec![Some(2), None, Some(0), Some(4), Some(3), Some(6), Some(5)]
+        );
+        assert_eq!(maximum_matching(&["A", "B", "A", "B"]).map(|partner| check(&["A", "B", "A", "B"], &partner)), Ok(1));
+    }
+
+    #[test]
+    fn too_many_devices() {
+        let types: Vec<&str> = (0..MAX_DEVICES + 2).map(|position| ["A", "B"][position % 2]).collect();
+        assert!(maximum_matching(&types).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+// result of connecting all devices of every type by one tree
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Nets {
+    // positions of the devices of every net going around the room, nets in the order they start
+    Trees(Vec<Vec<usize>>),
+    // positions a < b < c < d, a and c of one net, b and d of another, so their trees would cross
+    Crossing([usize; 4]),
+    // position of the only device of its type
+    Alone(usize)
+}
+
+// trees inside of the room don't cross exactly when no two nets interleave going around the walls,
+// the trees can then follow the walls between neighbouring devices of the net. nets are opened
+// and closed like the pairs of a routable room, a net can only go on while nothing opened after it is open
+pub fn nets(types: &[&str]) -> Nets {
+    let mut left: HashMap<&str, usize> = HashMap::new();
+    for c in types {
+        *left.entry(c).or_default() += 1;
+    }
+
+    if let Some(position) = types.iter().position(|c| left[c] == 1) {
+        return Nets::Alone(position);
+    }
+
+    // open nets with the position of their last device so far
+    let mut open: Vec<(&str, usize)> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut trees: Vec<Vec<usize>> = Vec::new();
+    for (position, c) in types.iter().enumerate() {
+        match open.last() {
+            Some((top, _)) if top == c => {}
+            _ if index.contains_key(c) => {
+                // `c` is open below the net on top, whose next device comes later
+                let (top, top_last) = *open.last().unwrap();
+                let (_, c_last) = open.iter().find(|(open_c, _)| open_c == c).unwrap();
+                let top_next = position + 1 + types[position + 1..].iter().position(|other| *other == top).unwrap();
+                return Nets::Crossing([*c_last, top_last, position, top_next]);
+            }
+            _ => {
+                index.insert(c, trees.len());
+                trees.push(Vec::new());
+                open.push((c, position));
+            }
+        }
+
+        trees[index[c]].push(position);
+        open.last_mut().unwrap().1 = position;
+
+        let count = left.get_mut(c).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            open.pop();
+        }
+    }
+
+    Nets::Trees(trees)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_and_side_by_side() {
+        assert_eq!(nets(&["A", "B", "B", "A", "C", "C"]), Nets::Trees(vec![vec![0, 3], vec![1, 2], vec![4, 5]]));
+        // a net goes on around the nets closed inside of it
+        assert_eq!(nets(&["A", "B", "B", "A", "C", "C", "A"]), Nets::Trees(vec![vec![0, 3, 6], vec![1, 2], vec![4, 5]]));
+        assert_eq!(nets(&["A", "A", "A"]), Nets::Trees(vec![vec![0, 1, 2]]));
+    }
+
+    #[test]
+    fn interleaving() {
+        assert_eq!(nets(&["A", "B", "A", "B"]), Nets::Crossing([0, 1, 2, 3]));
+        // the net on top goes on later, so it crosses the one coming back
+        assert_eq!(nets(&["A", "A", "B", "C", "C", "A", "B"]), Nets::Crossing([1, 2, 5, 6]));
+        assert_eq!(nets(&["A", "B", "C", "B", "A", "C"]), Nets::Crossing([1, 2, 3, 5]));
+    }
+
+    #[test]
+    fn alone() {
+        assert_eq!(nets(&["A", "B", "A"]), Nets::Alone(1));
+        assert_eq!(nets(&["A"]), Nets::Alone(0));
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+use crate::matching::{pair_neighbours, type_ids};
+use crate::{perimeter_position, Device, Task};
+
+// most partial pairings the search goes through before it gives up
+pub const MAX_STEPS: usize = 1_000_000;
+
+// where a device is in the room
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Place {
+    // clockwise position on the walls
+    Wall(i64),
+    // obstacle (from 0) and clockwise position on its edges
+    Edge(usize, i64),
+    // somewhere in the room, off the walls and obstacles
+    Free,
+    // strictly inside of an obstacle, no cable can get to it
+    Inside(usize)
+}
+
+pub fn place(task: &Task, device: &Device) -> Place {
+    if let Some(position) = task.task_header.wall_position(device.y, device.x) {
+        return Place::Wall(position);
+    }
+
+    // obstacles don't touch, so there is at most one around the device
+    for (index, obstacle) in task.obstacles.iter().enumerate() {
+        if (obstacle.top..=obstacle.bottom).contains(&device.y) && (obstacle.left..=obstacle.right).contains(&device.x) {
+            return match perimeter_position(
+                obstacle.bottom - obstacle.top, obstacle.right - obstacle.left,
+                device.y - obstacle.top, device.x - obstacle.left
+            ) {
+                Some(position) => Place::Edge(index, position),
+                None => Place::Inside(index)
+            };
+        }
+    }
+
+    Place::Free
+}
+
+// pairs of devices (by position in `task.devices`) connected without crossings around the obstacles,
+// None if there are none. Err when the search gives up
+pub fn route_among_obstacles(task: &Task) -> Result<Option<Vec<(usize, usize)>>, String> {
+    let boundaries = match boundaries(task) {
+        Some(boundaries) => boundaries,
+        None => return Ok(None)
+    };
+
+    let types = type_ids(task.devices.iter().map(|device| device.c.as_str()));
+
+    let mut partner = vec![None; task.devices.len()];
+    for boundary in &boundaries {
+        pair_ring_neighbours(boundary, &types, &mut partner);
+    }
+
+    let mut search = Search { boundaries: &boundaries, types: &types, partner, steps: 0 };
+    if !search.run()? {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        search.partner
+            .iter()
+            .enumerate()
+            .filter_map(|(device, partner)| partner.filter(|partner| *partner > device).map(|partner| (device, partner)))
+            .collect()
+    ))
+}
+
+// devices along every boundary of the room: the walls clockwise, then the edges of every obstacle
+// anticlockwise, so the room is on the same side going along any of them. free devices are
+// boundaries on their own. None if a device is inside of an obstacle
+fn boundaries(task: &Task) -> Option<Vec<Vec<usize>>> {
+    let mut walls: Vec<(i64, usize)> = Vec::new();
+    let mut edges: Vec<Vec<(i64, usize)>> = vec![Vec::new(); task.obstacles.len()];
+    let mut free: Vec<Vec<usize>> = Vec::new();
+
+    for (device, place) in task.devices.iter().map(|device| place(task, device)).enumerate() {
+        match place {
+            Place::Wall(position) => walls.push((position, device)),
+            Place::Edge(obstacle, position) => edges[obstacle].push((position, device)),
+            Place::Free => free.push(vec![device]),
+            Place::Inside(_) => return None
+        }
+    }
+
+    let mut boundaries = Vec::new();
+    walls.sort_by_key(|(position, _)| *position);
+    boundaries.push(walls.into_iter().map(|(_, device)| device).collect());
+    for mut edge in edges {
+        edge.sort_by_key(|(position, _)| *position);
+        boundaries.push(edge.into_iter().rev().map(|(_, device)| device).collect());
+    }
+    boundaries.extend(free);
+
+    Some(boundaries)
+}
+
+// pairs neighbours of the same type along a closed boundary, including the last with the first.
+// any routing can be changed to connect them, so no search is needed for these
+fn pair_ring_neighbours(boundary: &[usize], types: &[usize], partner: &mut [Option<usize>]) {
+    let left = pair_neighbours(boundary, types, partner);
+
+    let (mut first, mut last) = (0, left.len());
+    while last - first >= 2 && types[left[first]] == types[left[last - 1]] {
+        partner[left[first]] = Some(left[last - 1]);
+        partner[left[last - 1]] = Some(left[first]);
+        first += 1;
+        last -= 1;
+    }
+}
+
+// whether the cables of `partner` can go around the obstacles without crossing, devices without a
+// partner don't matter. A cable between two boundaries joins them into one: along the first up to the
+// cable, over to the second, all the way around it, back and on along the first. Cables between
+// devices of one boundary then must not interleave, like the cables of a room without obstacles.
+// Boundaries are rings of devices, so joining two of them only relinks the neighbours of the cable
+fn routable(boundaries: &[Vec<usize>], partner: &[Option<usize>]) -> bool {
+    let mut next = vec![usize::MAX; partner.len()];
+    let mut previous = vec![usize::MAX; partner.len()];
+    let mut owner = vec![usize::MAX; partner.len()];
+    for (index, boundary) in boundaries.iter().enumerate() {
+        let ring: Vec<usize> = boundary.iter().copied().filter(|device| partner[*device].is_some()).collect();
+        for (at, device) in ring.iter().enumerate() {
+            next[*device] = ring[(at + 1) % ring.len()];
+            previous[*device] = ring[(at + ring.len() - 1) % ring.len()];
+            owner[*device] = index;
+        }
+    }
+
+    // boundaries joined into another one point to it
+    let mut joined: Vec<usize> = (0..boundaries.len()).collect();
+    let find = |joined: &mut Vec<usize>, mut boundary: usize| {
+        while joined[boundary] != boundary {
+            joined[boundary] = joined[joined[boundary]];
+            boundary = joined[boundary];
+        }
+        boundary
+    };
+
+    let mut used = vec![false; partner.len()];
+    for device in 0..partner.len() {
+        let other = match partner[device] {
+            Some(other) if other > device => other,
+            _ => continue
+        };
+
+        let (a, b) = (find(&mut joined, owner[device]), find(&mut joined, owner[other]));
+        if a == b {
+            continue;
+        }
+        joined[b] = a;
+        used[device] = true;
+        used[other] = true;
+
+        // the ring of `device` without it goes on with the ring of `other` without it
+        let (before_device, after_device) = (previous[device], next[device]);
+        let (before_other, after_other) = (previous[other], next[other]);
+        let ends = match (after_device == device, after_other == other) {
+            (true, true) => Vec::new(),
+            (true, false) => vec![(before_other, after_other)],
+            (false, true) => vec![(before_device, after_device)],
+            (false, false) => vec![(before_device, after_other), (before_other, after_device)]
+        };
+        for (first, second) in ends {
+            next[first] = second;
+            previous[second] = first;
+        }
+    }
+
+    let mut seen = used;
+    for start in 0..partner.len() {
+        if seen[start] || partner[start].is_none() {
+            continue;
+        }
+
+        let mut open: Vec<usize> = Vec::new();
+        let mut device = start;
+        loop {
+            seen[device] = true;
+            if open.last().copied() == partner[device] {
+                open.pop();
+            } else {
+                open.push(device);
+            }
+            device = next[device];
+            if device == start {
+                break;
+            }
+        }
+        if !open.is_empty() {
+            return false;
+        }
+    }
+
+    true
+}
+
+// backtracking over the partners of devices not paired yet, every partial pairing is checked
+// so that dead ends are left as soon as they appear. the alternatives left are kept on a stack
+// instead of recursing, there can be as many of them as devices
+struct Search<'a> {
+    boundaries: &'a [Vec<usize>],
+    types: &'a [usize],
+    partner: Vec<Option<usize>>,
+    steps: usize
+}
+
+// a partial pairing with alternatives left: `device` is paired with `others[tried - 1]`,
+// `forced` were paired before because they had no other choice
+struct Frame {
+    forced: Vec<usize>,
+    device: usize,
+    others: Vec<usize>,
+    tried: usize
+}
+
+// what is left of a partial pairing
+enum Node {
+    DeadEnd,
+    Done,
+    // the first device of the type with the fewest devices left and its possible partners
+    Choice(usize, Vec<usize>)
+}
+
+impl Search<'_> {
+    fn run(&mut self) -> Result<bool, String> {
+        let mut stack: Vec<Frame> = Vec::new();
+        loop {
+            self.steps += 1;
+            if self.steps > MAX_STEPS {
+                return Err(format!("gave up after {} partial pairings, the room might be routable", MAX_STEPS));
+            }
+
+            let mut forced = Vec::new();
+            match self.expand(&mut forced) {
+                Node::Done => return Ok(true),
+                Node::Choice(device, others) => stack.push(Frame { forced, device, others, tried: 0 }),
+                Node::DeadEnd => self.unpair(&forced)
+            }
+
+            // on with the next alternative of the last partial pairing that has one
+            loop {
+                let frame = match stack.last_mut() {
+                    Some(frame) => frame,
+                    None => return Ok(false)
+                };
+                if frame.tried > 0 {
+                    self.partner[frame.device] = None;
+                    self.partner[frame.others[frame.tried - 1]] = None;
+                }
+                if let Some(&other) = frame.others.get(frame.tried) {
+                    self.partner[frame.device] = Some(other);
+                    self.partner[other] = Some(frame.device);
+                    frame.tried += 1;
+                    break;
+                }
+
+                let frame = stack.pop().unwrap();
+                self.unpair(&frame.forced);
+            }
+        }
+    }
+
+    // pairs the types with two devices left, they have no other choice, and checks the pairing
+    fn expand(&mut self, forced: &mut Vec<usize>) -> Node {
+        let mut waiting: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (device, partner) in self.partner.iter().enumerate() {
+            if partner.is_none() {
+                waiting.entry(self.types[device]).or_default().push(device);
+            }
+        }
+        if waiting.values().any(|devices| !devices.len().is_multiple_of(2)) {
+            return Node::DeadEnd;
+        }
+
+        waiting.retain(|_, devices| {
+            if devices.len() > 2 {
+                return true;
+            }
+            self.partner[devices[0]] = Some(devices[1]);
+            self.partner[devices[1]] = Some(devices[0]);
+            forced.extend_from_slice(devices);
+            false
+        });
+
+        if !routable(self.boundaries, &self.partner) {
+            return Node::DeadEnd;
+        }
+
+        // the type with the fewest devices left has the fewest choices
+        match waiting.into_values().min_by_key(|devices| (devices.len(), devices[0])) {
+            Some(devices) => Node::Choice(devices[0], devices[1..].to_vec()),
+            None => Node::Done
+        }
+    }
+
+    fn unpair(&mut self, devices: &[usize]) {
+        for device in devices {
+            self.partner[*device] = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn route(input: &str) -> Option<Vec<(usize, usize)>> {
+        let task = parse(input).unwrap().remove(0);
+        let cables = route_among_obstacles(&task).unwrap();
+        if let Some(cables) = &cables {
+            let mut paired = vec![false; task.devices.len()];
+            for (first, second) in cables {
+                assert_eq!(task.devices[*first].c, task.devices[*second].c, "{}", input);
+                paired[*first] = true;
+                paired[*second] = true;
+            }
+            assert!(paired.iter().all(|paired| *paired), "{}", input);
+        }
+        cables
+    }
+
+    fn pairing(devices: usize, cables: &[(usize, usize)]) -> Vec<Option<usize>> {
+        let mut partner = vec![None; devices];
+        for (first, second) in cables {
+            partner[*first] = Some(*second);
+            partner[*second] = Some(*first);
+        }
+        partner
+    }
+
+    #[test]
+    fn routable_boundaries() {
+        // one boundary, like a room without obstacles
+        assert!(routable(&[vec![0, 1, 2, 3]], &pairing(4, &[(0, 1), (2, 3)])));
+        assert!(!routable(&[vec![0, 1, 2, 3]], &pairing(4, &[(0, 2), (1, 3)])));
+        // devices without a partner don't matter
+        assert!(routable(&[vec![0, 1, 2, 3, 4]], &pairing(5, &[(0, 2), (3, 4)])));
+
+        // a cable to an obstacle joins its edges in after the cable
+        let boundaries = [vec![0, 1, 2], vec![3, 4, 5]];
+        assert!(routable(&boundaries, &pairing(6, &[(0, 3), (1, 5), (2, 4)])));
+        assert!(!routable(&boundaries, &pairing(6, &[(0, 3), (1, 4), (2, 5)])));
+
+        // free devices are boundaries on their own
+        assert!(routable(&[vec![0, 1], vec![2], vec![3]], &pairing(4, &[(0, 2), (1, 3)])));
+        assert!(routable(&[vec![0], vec![1], vec![2], vec![3]], &pairing(4, &[(0, 3), (1, 2)])));
+    }
+
+    #[test]
+    fn hand_made_rooms() {
+        // from the walls to both sides of the obstacle
+        assert_eq!(route("1\n4 4 4 1\n0 2 A\n1 2 A\n4 2 B\n3 2 B\n1 1 3 3\n"), Some(vec![(0, 1), (2, 3)]));
+        // crossing along the walls, the obstacle doesn't help
+        assert_eq!(route("1\n4 4 4 1\n0 1 A\n0 3 B\n4 3 A\n4 1 B\n1 1 3 3\n"), None);
+        // no cable gets inside of an obstacle
+        assert_eq!(route("1\n6 6 2 1\n0 1 A\n3 3 A\n2 2 4 4\n"), None);
+        // free devices can be reached from anywhere
+        assert!(route("1\n6 6 4 0\n2 2 A\n3 3 B\n4 4 A\n0 3 B\n").is_some());
+        assert!(route("1\n6 6 6 0\n0 1 A\n0 2 B\n0 3 A\n2 2 A\n2 3 B\n0 4 A\n").is_some());
+        // an odd number of devices of a type
+        assert_eq!(route("1\n6 6 3 1\n0 1 A\n1 2 A\n5 5 A\n1 1 3 3\n"), None);
+    }
+
+    #[test]
+    fn many_free_devices() {
+        let mut input = "1\n1000 1000 40000\n".to_owned();
+        for device in 0..40000 {
+            input.push_str(&format!("{} {} T{}\n", 1 + device / 998, 1 + device % 998, device % 20000));
+        }
+        assert_eq!(route(&input).map(|cables| cables.len()), Some(20000));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::error::ErrorKind;
+use crate::MAX_SIDE;
+
+// rooms given by their corners, (y, x) like devices. an edge goes from a corner to the next one
+
+// most corners of a room
+pub const MAX_CORNERS: usize = 1000;
+
+// the corners of a simple polygon turned clockwise (y grows down), starting at the same corner.
+// edges may only meet neighbouring edges and only at their common corner
+pub fn simple_polygon(corners: &[(i32, i32)]) -> Result<Vec<(i32, i32)>, ErrorKind> {
+    let m = corners.len();
+    if !(3..=MAX_CORNERS).contains(&m) {
+        return Err(ErrorKind::OutOfRange { what: "m", value: m as i64, min: 3, max: MAX_CORNERS as i64 });
+    }
+
+    for (y, x) in corners {
+        for (what, value) in [("y", y), ("x", x)] {
+            if !(0..=MAX_SIDE).contains(value) {
+                return Err(ErrorKind::OutOfRange { what, value: *value as i64, min: 0, max: MAX_SIDE as i64 });
+            }
+        }
+    }
+
+    for i in 0..m {
+        if corners[i] == corners[(i + 1) % m] {
+            return Err(ErrorKind::EdgeWithoutLength(i + 1));
+        }
+    }
+
+    let edge = |i: usize| (corners[i], corners[(i + 1) % m]);
+    for i in 0..m {
+        for j in i + 1..m {
+            let (a, b) = edge(i);
+            let (c, d) = edge(j);
+            let crossing = if j == i + 1 {
+                // they share b == c, the far ends must stay off the other edge
+                on_segment(a, b, d) || on_segment(c, d, a)
+            } else if i == 0 && j == m - 1 {
+                // they share a == d
+                on_segment(a, b, c) || on_segment(c, d, b)
+            } else {
+                segments_touch(a, b, c, d)
+            };
+
+            if crossing {
+                return Err(ErrorKind::EdgesCross(i + 1, j + 1));
+            }
+        }
+    }
+
+    let area: i128 = (0..m).map(|i| {
+        let (a, b) = edge(i);
+        a.1 as i128 * b.0 as i128 - b.1 as i128 * a.0 as i128
+    }).sum();
+
+    if area == 0 {
+        return Err(ErrorKind::RoomWithoutArea);
+    }
+
+    let mut clockwise = corners.to_vec();
+    if area < 0 {
+        clockwise[1..].reverse();
+    }
+    Ok(clockwise)
+}
+
+// distance along the walls from the first corner, measured |dy| + |dx| on every edge so it stays
+// a whole number on slanted edges too. None for places off the walls. every corner belongs to the
+// edge it ends, except the first corner
+pub fn boundary_position(corners: &[(i32, i32)], y: i32, x: i32) -> Option<i64> {
+    let mut length = 0;
+    for i in 0..corners.len() {
+        let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+        if on_segment(a, b, (y, x)) {
+            return Some(length + (y as i64 - a.0 as i64).abs() + (x as i64 - a.1 as i64).abs());
+        }
+        length += (b.0 as i64 - a.0 as i64).abs() + (b.1 as i64 - a.1 as i64).abs();
+    }
+    None
+}
+
+// whether a place off the walls is inside of the room, counts the edges crossed going right from it
+pub fn inside(corners: &[(i32, i32)], y: i32, x: i32) -> bool {
+    let (y, x) = (y as i64, x as i64);
+    let mut crossings = 0;
+    for i in 0..corners.len() {
+        let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+        let (ay, ax, by, bx) = (a.0 as i64, a.1 as i64, b.0 as i64, b.1 as i64);
+        if (ay > y) == (by > y) {
+            continue;
+        }
+
+        // the edge crosses the line of the place right of it when this has the sign of by - ay
+        let right = (bx - ax) * (y - ay) - (x - ax) * (by - ay);
+        if (right > 0) == (by > ay) {
+            crossings += 1;
+        }
+    }
+    crossings % 2 == 1
+}
+
+// whether a rectangle lies inside of the room without touching its walls
+pub fn contains_rectangle(corners: &[(i32, i32)], top: i32, left: i32, bottom: i32, right: i32) -> bool {
+    let rectangle = [(top, left), (top, right), (bottom, right), (bottom, left)];
+    if rectangle.iter().any(|(y, x)| boundary_position(corners, *y, *x).is_some() || !inside(corners, *y, *x)) {
+        return false;
+    }
+
+    (0..corners.len()).all(|i| {
+        let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+        (0..4).all(|j| !segments_touch(a, b, rectangle[j], rectangle[(j + 1) % 4]))
+    })
+}
+
+// > 0 when `c` is clockwise from `b` seen from `a` (y grows down), 0 when they are on one line
+fn turn(a: (i32, i32), b: (i32, i32), c: (i32, i32)) -> i64 {
+    let (ay, ax, by, bx, cy, cx) = (a.0 as i64, a.1 as i64, b.0 as i64, b.1 as i64, c.0 as i64, c.1 as i64);
+    (bx - ax) * (cy - ay) - (by - ay) * (cx - ax)
+}
+
+fn on_segment(a: (i32, i32), b: (i32, i32), p: (i32, i32)) -> bool {
+    turn(a, b, p) == 0
+        && a.0.min(b.0) <= p.0 && p.0 <= a.0.max(b.0)
+        && a.1.min(b.1) <= p.1 && p.1 <= a.1.max(b.1)
+}
+
+// whether two segments have a point in common, ends included
+fn segments_touch(a: (i32, i32), b: (i32, i32), c: (i32, i32), d: (i32, i32)) -> bool {
+    let (abc, abd) = (turn(a, b, c).signum(), turn(a, b, d).signum());
+    let (cda, cdb) = (turn(c, d, a).signum(), turn(c, d, b).signum());
+    if abc * abd < 0 && cda * cdb < 0 {
+        return true;
+    }
+    on_segment(a, b, c) || on_segment(a, b, d) || on_segment(c, d, a) || on_segment(c, d, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clockwise_rooms() {
+        let square = [(0, 0), (0, 4), (4, 4), (4, 0)];
+        assert_eq!(simple_polygon(&square), Ok(square.to_vec()));
+        assert_eq!(simple_polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]), Ok(square.to_vec()));
+        // a corner in the middle of a straight wall is fine
+        assert!(simple_polygon(&[(0, 0), (0, 2), (0, 4), (4, 4), (4, 0)]).is_ok());
+    }
+
+    #[test]
+    fn broken_rooms() {
+        // bowtie, the second and the fourth edge cross
+        assert_eq!(simple_polygon(&[(0, 0), (0, 4), (4, 0), (4, 4)]), Err(ErrorKind::EdgesCross(2, 4)));
+        // going back along the edge before
+        assert_eq!(simple_polygon(&[(0, 0), (0, 4), (0, 2), (4, 2)]), Err(ErrorKind::EdgesCross(1, 2)));
+        // every corner on one line
+        assert!(simple_polygon(&[(0, 0), (0, 2), (0, 4)]).is_err());
+        // a corner on an edge that isn't its neighbour
+        assert_eq!(
+            simple_polygon(&[(0, 0), (0, 4), (4, 4), (2, 0), (4, 0)]).unwrap_err(),
+            ErrorKind::EdgesCross(3, 5)
+        );
+        assert_eq!(simple_polygon(&[(0, 0), (0, 4), (0, 4), (4, 0)]), Err(ErrorKind::EdgeWithoutLength(2)));
+        assert!(matches!(simple_polygon(&[(0, 0), (0, 4)]), Err(ErrorKind::OutOfRange { what: "m", .. })));
+        assert!(matches!(simple_polygon(&[(0, 0), (0, -4), (4, 0)]), Err(ErrorKind::OutOfRange { what: "x", .. })));
+    }
+
+    #[test]
+    fn walls_and_inside() {
+        let room = simple_polygon(&[(0, 0), (0, 6), (3, 6), (3, 3), (6, 3), (6, 0)]).unwrap();
+        assert_eq!(boundary_position(&room, 0, 0), Some(0));
+        assert_eq!(boundary_position(&room, 2, 6), Some(8));
+        assert_eq!(boundary_position(&room, 6, 1), Some(17));
+        assert_eq!(boundary_position(&room, 1, 1), None);
+
+        assert!(inside(&room, 1, 1));
+        assert!(inside(&room, 5, 2));
+        assert!(!inside(&room, 5, 5));
+        assert!(contains_rectangle(&room, 1, 1, 2, 2));
+        assert!(!contains_rectangle(&room, 1, 1, 5, 5));
+    }
+}
@@ -0,0 +1,207 @@
+use std::f64::consts::PI;
+use crate::layers::crossed;
+use crate::matching::fixed_pairing;
+use crate::{devices_around_the_room, Task};
+
+// space around the room for the labels, in pixels
+const MARGIN: f64 = 60.0;
+// longer side of the room in pixels, unless that would make a unit bigger than `MAX_UNIT`
+const SIZE: f64 = 800.0;
+const MAX_UNIT: f64 = 60.0;
+// points of every cable curve
+const SAMPLES: usize = 32;
+
+// picture of the room: walls, devices labelled with their type and cables of the fixed pairing.
+// cables are straight chords of a disk mapped onto the room from its centre, so cables that don't
+// cross on the walls don't cross in the picture either. crossing cables are red, obstacles gray.
+// the map needs every wall to be seen from the centre, polygon rooms that aren't get no cables
+pub fn render(task: &Task) -> String {
+    let (h, w) = (task.task_header.h as f64, task.task_header.w as f64);
+    let unit = (SIZE / h.max(w)).min(MAX_UNIT);
+    let point = |y: f64, x: f64| (MARGIN + x * unit, MARGIN + y * unit);
+
+    let mut lines = vec![
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" font-family=\"sans-serif\" font-size=\"12\">",
+            2.0 * MARGIN + w * unit, 2.0 * MARGIN + h * unit
+        ),
+    ];
+
+    match &task.task_header.corners {
+        Some(corners) => {
+            let points: Vec<String> = corners.iter().map(|(y, x)| {
+                let (px, py) = point(*y as f64, *x as f64);
+                format!("{:.1},{:.1}", px, py)
+            }).collect();
+            lines.push(format!("  <polygon points=\"{}\" fill=\"white\" stroke=\"black\" stroke-width=\"2\"/>", points.join(" ")));
+        }
+        None => lines.push(format!(
+            "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"white\" stroke=\"black\" stroke-width=\"2\"/>",
+            MARGIN, MARGIN, w * unit, h * unit
+        ))
+    }
+
+    for obstacle in &task.obstacles {
+        let (px, py) = point(obstacle.top as f64, obstacle.left as f64);
+        lines.push(format!(
+            "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"lightgray\" stroke=\"black\"/>",
+            px, py, (obstacle.right - obstacle.left) as f64 * unit, (obstacle.bottom - obstacle.top) as f64 * unit
+        ));
+    }
+    if !task.on_the_walls() {
+        eprintln!("task {}: only cables between devices on the walls are drawn and they don't avoid obstacles", task.number);
+    }
+
+    let devices_in_correct_order = devices_around_the_room(&task.task_header, &task.devices);
+    let types: Vec<&str> = devices_in_correct_order.iter().map(|device| device.c.as_str()).collect();
+    let partner = fixed_pairing(&types);
+    let cables: Vec<(usize, usize)> = partner
+        .iter()
+        .enumerate()
+        .filter_map(|(position, partner)| partner.filter(|partner| *partner > position).map(|partner| (position, partner)))
+        .collect();
+    let crossed = crossed(&cables);
+
+    // rooms whose walls can't all be seen from the centre get no cables
+    let (centre, drawn) = match centre(task) {
+        Some(centre) => (centre, cables.len()),
+        None => {
+            eprintln!("task {}: cables are only drawn in rooms whose walls can all be seen from the centre", task.number);
+            ((0.0, 0.0), 0)
+        }
+    };
+    for (cable, (first, second)) in cables.iter().enumerate().take(drawn) {
+        let (first, second) = (devices_in_correct_order[*first], devices_in_correct_order[*second]);
+        let from = angle(task, first.y, first.x);
+        let to = angle(task, second.y, second.x);
+
+        let mut path = String::new();
+        for sample in 0..=SAMPLES {
+            let t = sample as f64 / SAMPLES as f64;
+            let (y, x) = disk_to_room(task, centre, (1.0 - t) * from.sin() + t * to.sin(), (1.0 - t) * from.cos() + t * to.cos());
+            let (px, py) = point(y, x);
+            path.push_str(&format!("{}{:.1},{:.1}", if sample == 0 { "M" } else { " L" }, px, py));
+        }
+
+        let colour = if crossed[cable] { "red" } else { "steelblue" };
+        lines.push(format!("  <path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>", path, colour));
+    }
+
+    let mut connected = vec![false; task.devices.len() + 1];
+    for (device, partner) in devices_in_correct_order.iter().zip(&partner) {
+        connected[device.index] = partner.is_some();
+    }
+
+    for device in &task.devices {
+        let (px, py) = point(device.y as f64, device.x as f64);
+        let fill = if connected[device.index] { "black" } else { "white" };
+        lines.push(format!("  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" fill=\"{}\" stroke=\"black\"/>", px, py, fill));
+
+        // labels go outside of the walls
+        let (dx, dy, anchor) = if device.y == 0 {
+            (0.0, -10.0, "middle")
+        } else if device.y == task.task_header.h {
+            (0.0, 20.0, "middle")
+        } else if device.x == 0 {
+            (-8.0, 4.0, "end")
+        } else if device.x == task.task_header.w {
+            (8.0, 4.0, "start")
+        } else {
+            (0.0, -8.0, "middle")
+        };
+        lines.push(format!(
+            "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"{}\">{} {}</text>",
+            px + dx, py + dy, anchor, device.index, escape(&device.c)
+        ));
+    }
+
+    lines.push("</svg>".to_owned());
+    lines.join("\n")
+}
+
+// angle of a place on the walls, the whole way around is 2 pi
+fn angle(task: &Task, y: i32, x: i32) -> f64 {
+    let position = task.task_header.wall_position(y, x).unwrap() as f64;
+    2.0 * PI * position / perimeter(task)
+}
+
+// length of the walls the way `wall_position` measures it
+fn perimeter(task: &Task) -> f64 {
+    match &task.task_header.corners {
+        Some(corners) => edges(corners).map(|(a, b)| (b.0 - a.0).abs() + (b.1 - a.1).abs()).sum(),
+        None => 2.0 * (task.task_header.h as f64 + task.task_header.w as f64)
+    }
+}
+
+// place on the walls at `position`, clockwise like `wall_position`
+fn wall_point(task: &Task, position: f64) -> (f64, f64) {
+    let (h, w) = (task.task_header.h as f64, task.task_header.w as f64);
+    let corners = match &task.task_header.corners {
+        Some(corners) => corners,
+        None => return if position <= w {
+            (0.0, position)
+        } else if position <= w + h {
+            (position - w, w)
+        } else if position <= 2.0 * w + h {
+            (h, 2.0 * w + h - position)
+        } else {
+            (2.0 * (w + h) - position, 0.0)
+        }
+    };
+
+    let mut left = position;
+    for (a, b) in edges(corners) {
+        let length = (b.0 - a.0).abs() + (b.1 - a.1).abs();
+        if left <= length {
+            let t = left / length;
+            return (a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1));
+        }
+        left -= length;
+    }
+    (corners[0].0 as f64, corners[0].1 as f64)
+}
+
+// middle of a rectangle, centroid of a polygon room when every wall can be seen from it
+fn centre(task: &Task) -> Option<(f64, f64)> {
+    let corners = match &task.task_header.corners {
+        Some(corners) => corners,
+        None => return Some((task.task_header.h as f64 / 2.0, task.task_header.w as f64 / 2.0))
+    };
+
+    let (mut area, mut y, mut x) = (0.0, 0.0, 0.0);
+    for (a, b) in edges(corners) {
+        let cross = a.0 * b.1 - b.0 * a.1;
+        area += cross / 2.0;
+        y += (a.0 + b.0) * cross / 6.0;
+        x += (a.1 + b.1) * cross / 6.0;
+    }
+    let centre = (y / area, x / area);
+
+    // corners go clockwise, so the centre has to be clockwise from every edge
+    let sees = edges(corners).all(|(a, b)| (b.1 - a.1) * (centre.0 - a.0) - (b.0 - a.0) * (centre.1 - a.1) > 0.0);
+    if sees { Some(centre) } else { None }
+}
+
+fn edges(corners: &[(i32, i32)]) -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+    let point = |(y, x): (i32, i32)| (y as f64, x as f64);
+    (0..corners.len()).map(move |i| (point(corners[i]), point(corners[(i + 1) % corners.len()])))
+}
+
+// point (sin, cos) of the unit disk to (y, x) in the room: the same angle gives the place on the walls,
+// the distance from the centre is scaled towards it. the distance is pulled towards the centre first
+// (monotonically, so nothing starts crossing), otherwise cables between close devices hug the wall
+fn disk_to_room(task: &Task, centre: (f64, f64), sin: f64, cos: f64) -> (f64, f64) {
+    let radius = 1.0 - (1.0 - sin.hypot(cos).min(1.0)).sqrt();
+    let perimeter = perimeter(task);
+    let mut position = sin.atan2(cos) / (2.0 * PI) * perimeter;
+    if position < 0.0 {
+        position += perimeter;
+    }
+
+    let (wall_y, wall_x) = wall_point(task, position);
+    (centre.0 + radius * (wall_y - centre.0), centre.1 + radius * (wall_x - centre.1))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,16 @@
+// seeded generator of the randomized tests, so that a failure shows up on every run
+pub struct Random {
+    seed: u64
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random { seed }
+    }
+
+    // some number in 0..below
+    pub fn below(&mut self, below: usize) -> usize {
+        self.seed = self.seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.seed >> 33) as usize % below
+    }
+}
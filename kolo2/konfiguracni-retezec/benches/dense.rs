@@ -0,0 +1,43 @@
+//! Dense elimination against the row-by-row `Elimination`, `cargo bench`.
+//! Pass sizes as arguments to override the default ones, e.g. `cargo bench -- 1024 4096`.
+
+use std::time::{Duration, Instant};
+use konfiguracni_retezec::dense::default_threads;
+use konfiguracni_retezec::random::{Generator, Rng};
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+fn main() {
+    let mut sizes: Vec<usize> = std::env::args().skip(1).filter_map(|arg| arg.parse().ok()).collect();
+    if sizes.is_empty() {
+        sizes = vec![512, 1024, 2048, 4096];
+    }
+
+    // always show a few thread counts, even on machines with less cores
+    let mut thread_counts = vec![1, 2, 4];
+    while thread_counts.last().unwrap() * 2 <= default_threads() {
+        thread_counts.push(thread_counts.last().unwrap() * 2);
+    }
+
+    println!("{:>6} {:>14} {:>8} {:>14} {:>9}", "size", "solver", "threads", "time", "speedup");
+
+    for size in sizes {
+        let mut rng = Rng::new(size as u64);
+        let system = Generator { switches: size, scripts: size, density: 0.5, planted: true }.generate(&mut rng).system();
+
+        let (elimination, baseline) = time(|| system.elimination());
+        println!("{:>6} {:>14} {:>8} {:>14?} {:>9}", size, "elimination", 1, baseline, "1.00x");
+
+        for threads in &thread_counts {
+            let (dense, elapsed) = time(|| system.dense(*threads));
+            assert_eq!(dense.rank, elimination.rank());
+
+            let speedup = baseline.as_secs_f64() / elapsed.as_secs_f64();
+            println!("{:>6} {:>14} {:>8} {:>14?} {:>8.2}x", size, "dense", threads, elapsed, speedup);
+        }
+    }
+}
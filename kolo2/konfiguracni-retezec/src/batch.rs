@@ -0,0 +1,108 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use crate::System;
+
+/// Splits a batch file into its instances: a line with the number of instances, then that many
+/// `N M` blocks (each optionally followed by its `names` section), blank lines are skipped. Every block
+/// is parsed like a single system and only fails its own instance. A block ends early at the first line
+/// that isn't shaped like a switch line (`k` followed by `k` scripts), that line starts the next block and
+/// the short block fails. Only a `1 M` header can pass for a switch line, so a short block right before one
+/// still takes it in. After a broken `N M` line there's no telling where the next block starts and the
+/// rest of the instances fail too. Errors only if the count itself is wrong.
+pub fn split(contents: &str) -> Result<Vec<Result<System, String>>, String> {
+    let mut lines = contents.lines().filter(|line| !line.trim().is_empty()).peekable();
+
+    let count: usize = match lines.next().map(|line| line.trim().parse()) {
+        Some(Ok(count)) => count,
+        _ => return Err("failed to parse number of instances".to_owned()),
+    };
+
+    let mut instances: Vec<Result<System, String>> = Vec::with_capacity(count);
+    while instances.len() < count {
+        let header = match lines.next() {
+            Some(header) => header,
+            None => {
+                instances.push(Err("missing, the file ends before it".to_owned()));
+                continue;
+            }
+        };
+
+        let (switches, _) = match parse_header(header) {
+            Some(header) => header,
+            None => {
+                instances.push(Err(format!("failed to parse first line \"{}\"", header.trim())));
+                while instances.len() < count {
+                    instances.push(Err("can't be found after the malformed block before it".to_owned()));
+                }
+                break;
+            }
+        };
+
+        let mut block = vec![header];
+        while block.len() <= switches {
+            match lines.next_if(|line| is_switch_line(line)) {
+                Some(line) => block.push(line),
+                None => break,
+            }
+        }
+        let found = block.len() - 1;
+
+        if lines.peek().is_some_and(|line| line.trim() == "names") {
+            block.push(lines.next().unwrap());
+            while let Some(line) = lines.next_if(|line| line.trim_start().starts_with("switch ") || line.trim_start().starts_with("script ")) {
+                block.push(line);
+            }
+        }
+
+        if found < switches {
+            instances.push(Err(format!("expected {} switch lines, found {}", switches, found)));
+            continue;
+        }
+
+        // the same parser as for a single system
+        instances.push(System::parse(&block.join("\n")));
+    }
+
+    Ok(instances)
+}
+
+/// `k s_1 .. s_k`, the scripts themselves are read as leniently as in a single system
+fn is_switch_line(line: &str) -> bool {
+    let mut numbers = line.split_whitespace();
+    match numbers.next().map(|count| count.parse::<usize>()) {
+        Some(Ok(count)) => numbers.count() == count,
+        _ => false,
+    }
+}
+
+/// `N M`, both non-negative
+fn parse_header(line: &str) -> Option<(usize, usize)> {
+    let numbers: Vec<&str> = line.split_whitespace().collect();
+    match numbers[..] {
+        [switches, scripts] => Some((switches.parse().ok()?, scripts.parse().ok()?)),
+        _ => None,
+    }
+}
+
+/// Applies `f` to every item on up to `threads` threads, results in the order of the items.
+/// Threads take the next unprocessed item, so a few slow items don't hold back the rest.
+pub fn map_parallel<T: Sync, R: Send>(items: &[T], threads: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new((0..items.len()).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= items.len() {
+                    break;
+                }
+                let result = f(&items[index]);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    results.into_inner().unwrap().into_iter().map(|result| result.unwrap()).collect()
+}
@@ -0,0 +1,59 @@
+use std::time::{Duration, Instant};
+
+/// Limits for the search based solvers. Once a limit is hit, `spend` keeps
+/// returning false and the search is supposed to unwind with what it has.
+#[derive(Debug, Clone)]
+pub struct Budget {
+    deadline: Option<Instant>,
+    max_nodes: Option<u64>,
+    nodes: u64,
+    exceeded: bool,
+}
+
+impl Budget {
+    pub fn new(timeout: Option<Duration>, max_nodes: Option<u64>) -> Self {
+        Self {
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+            max_nodes,
+            nodes: 0,
+            exceeded: false,
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(None, None)
+    }
+
+    /// Accounts one explored node, returns false if the search has to stop
+    pub fn spend(&mut self) -> bool {
+        if self.exceeded {
+            return false;
+        }
+
+        if let Some(max_nodes) = self.max_nodes {
+            if self.nodes >= max_nodes {
+                self.exceeded = true;
+                return false;
+            }
+        }
+
+        // checking the clock on every node would be slower than the node itself
+        if let Some(deadline) = self.deadline {
+            if self.nodes.is_multiple_of(1024) && Instant::now() >= deadline {
+                self.exceeded = true;
+                return false;
+            }
+        }
+
+        self.nodes += 1;
+        true
+    }
+
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    pub fn exceeded(&self) -> bool {
+        self.exceeded
+    }
+}
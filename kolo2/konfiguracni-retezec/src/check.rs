@@ -0,0 +1,130 @@
+use std::collections::BTreeSet;
+
+/// Script `s` as the set of switches toggling it, the equation is their XOR = 1
+type Script = BTreeSet<usize>;
+
+/// Sum of some scripts, `switches XOR .. = constant`
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+struct Sum {
+    switches: BTreeSet<usize>,
+    constant: bool,
+}
+
+/// Checks a proof written by `proof::to_proof` against the input file, returns the number of steps.
+/// Shares no code with the solver, the input file is read here too.
+pub fn check(input: &str, proof: &str) -> Result<usize, String> {
+    let scripts = read_scripts(input)?;
+
+    let mut lines = proof.lines().filter(|line| !line.trim().is_empty());
+    let steps: usize = match lines.next().and_then(|line| line.trim().strip_prefix("steps ")).map(|count| count.trim().parse()) {
+        Some(Ok(steps)) if steps > 0 => steps,
+        _ => return Err("proof has to start with \"steps <count>\"".to_owned()),
+    };
+
+    let mut sums: Vec<Sum> = Vec::with_capacity(steps);
+    for line in lines {
+        let step = sums.len() + 1;
+        if step > steps {
+            return Err(format!("step {}: proof has only {} steps", step, steps));
+        }
+        let sum = check_step(line, step, &sums, &scripts).map_err(|e| format!("step {}: {}", step, e))?;
+        sums.push(sum);
+    }
+
+    if sums.len() < steps {
+        return Err(format!("proof ends after {} of {} steps", sums.len(), steps));
+    }
+    if sums[steps - 1] != (Sum { switches: BTreeSet::new(), constant: true }) {
+        return Err("the last step isn't 0 = 1".to_owned());
+    }
+
+    Ok(steps)
+}
+
+/// `n: [p + ]script s => <sum> = c`, returns the sum after the step
+fn check_step(line: &str, step: usize, sums: &[Sum], scripts: &[Script]) -> Result<Sum, String> {
+    let (number, rest) = line.split_once(':').ok_or("missing \"<step>:\"")?;
+    if number.trim().parse() != Ok(step) {
+        return Err(format!("expected step number {}, found \"{}\"", step, number.trim()));
+    }
+
+    let (addition, claim) = rest.split_once("=>").ok_or("missing \"=>\"")?;
+
+    let (previous, script) = match addition.split_once('+') {
+        Some((previous, script)) => {
+            let previous: usize = previous.trim().parse().map_err(|_| format!("\"{}\" isn't a step", previous.trim()))?;
+            if previous == 0 || previous >= step {
+                return Err(format!("step {} isn't before this one", previous));
+            }
+            (sums[previous - 1].clone(), script)
+        }
+        None => (Sum::default(), addition),
+    };
+
+    let script = match script.trim().strip_prefix("script ").map(|script| script.trim().parse::<usize>()) {
+        Some(Ok(script)) if (1..=scripts.len()).contains(&script) => script,
+        Some(Ok(script)) => return Err(format!("script {} doesn't exist", script)),
+        _ => return Err(format!("\"{}\" has to be \"script <number>\"", script.trim())),
+    };
+
+    let sum = Sum {
+        switches: previous.switches.symmetric_difference(&scripts[script - 1]).copied().collect(),
+        constant: !previous.constant,
+    };
+
+    if parse_sum(claim)? != sum {
+        return Err(format!("\"{}\" doesn't follow", claim.trim()));
+    }
+    Ok(sum)
+}
+
+/// `i_a XOR i_b .. = c` or `0 = c`
+fn parse_sum(claim: &str) -> Result<Sum, String> {
+    let malformed = || format!("\"{}\" has to be \"i_a XOR i_b .. = 0|1\"", claim.trim());
+
+    let (left, right) = claim.split_once('=').ok_or_else(malformed)?;
+    let constant = match right.trim() {
+        "0" => false,
+        "1" => true,
+        _ => return Err(malformed()),
+    };
+
+    let mut switches = BTreeSet::new();
+    if left.trim() != "0" {
+        for switch in left.split("XOR") {
+            let switch = switch.trim().strip_prefix("i_").and_then(|number| number.parse().ok()).ok_or_else(malformed)?;
+            if !switches.insert(switch) {
+                return Err(malformed());
+            }
+        }
+    }
+
+    Ok(Sum { switches, constant })
+}
+
+/// Scripts of the `N M` input, switch lines end with the file or a `names` line
+fn read_scripts(input: &str) -> Result<Vec<Script>, String> {
+    let mut lines = input.lines();
+    let header: Vec<usize> = lines
+        .next()
+        .unwrap_or("")
+        .split_whitespace()
+        .map(|number| number.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| "failed to parse first line of the input")?;
+    let (switches, count) = match header[..] {
+        [switches, count] => (switches, count),
+        _ => return Err("failed to parse first line of the input".to_owned()),
+    };
+
+    let mut scripts = vec![Script::new(); count];
+    for (index, line) in lines.take_while(|line| line.trim() != "names").take(switches).enumerate() {
+        for script in line.split_whitespace().skip(1).filter_map(|number| number.parse::<usize>().ok()) {
+            if (1..=count).contains(&script) {
+                scripts[script - 1].insert(index + 1);
+            }
+        }
+    }
+
+    Ok(scripts)
+}
@@ -0,0 +1,233 @@
+use std::thread;
+use crate::elimination::power_of_two;
+use crate::{System, Value};
+
+/// Columns eliminated together, the lookup table has 2^K rows
+const K: usize = 8;
+/// Words of a row processed at once, so that the matching part of the table stays in cache
+const WORDS_PER_BLOCK: usize = 32;
+
+/// Dense matrix over GF(2), rows packed into `u64` words one after another.
+/// The last column is the constant (right side) of the row.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DenseMatrix {
+    pub rows: usize,
+    pub cols: usize,
+    words: usize,
+    data: Vec<u64>,
+}
+
+/// Result of `DenseMatrix::eliminate`
+#[derive(Debug, Clone)]
+pub struct DenseElimination {
+    pub matrix: DenseMatrix,
+    pub rank: usize,
+    /// pivot column of row `i`, for `i < rank`
+    pub pivots: Vec<usize>,
+    pub consistent: bool,
+}
+
+impl DenseMatrix {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let words = cols.div_ceil(64);
+        Self { rows, cols, words, data: vec![0; rows * words] }
+    }
+
+    /// Augmented matrix of the system, one row per equation, column `i - 1` for switch `i`
+    pub fn from_system(system: &System) -> Self {
+        let mut matrix = Self::new(system.equations.len(), system.switches + 1);
+
+        for (row, equation) in system.equations.iter().enumerate() {
+            for expression in [&equation.left, &equation.right] {
+                for val in expression.values() {
+                    match val {
+                        Value::True => matrix.toggle(row, system.switches),
+                        Value::False => {}
+                        Value::Var(var) => matrix.toggle(row, *var as usize - 1),
+                    }
+                }
+            }
+        }
+
+        matrix
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.data[row * self.words + col / 64] >> (col % 64) & 1 == 1
+    }
+
+    pub fn toggle(&mut self, row: usize, col: usize) {
+        self.data[row * self.words + col / 64] ^= 1 << (col % 64)
+    }
+
+    fn row(&self, row: usize) -> &[u64] {
+        &self.data[row * self.words..(row + 1) * self.words]
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for word in 0..self.words {
+                self.data.swap(a * self.words + word, b * self.words + word)
+            }
+        }
+    }
+
+    /// row `target` ^= row `source`, only words from `from_word` on
+    fn add_row(&mut self, target: usize, source: usize, from_word: usize) {
+        for word in from_word..self.words {
+            let value = self.data[source * self.words + word];
+            self.data[target * self.words + word] ^= value
+        }
+    }
+
+    /// Bits of `row` at `cols` packed into a number, `cols[0]` being the lowest bit
+    fn bits(&self, row: usize, cols: &[usize]) -> usize {
+        cols.iter()
+            .enumerate()
+            .fold(0, |mask, (bit, col)| mask | (self.get(row, *col) as usize) << bit)
+    }
+
+    /// Method of the Four Russians: eliminates `K` columns at a time using a table of all
+    /// combinations of their pivot rows, the rest of the rows are updated by `threads` threads.
+    /// The constant column is never used as a pivot.
+    pub fn eliminate(mut self, threads: usize) -> DenseElimination {
+        let threads = threads.max(1);
+        let variables = self.cols - 1;
+        let mut rank = 0;
+        let mut pivots: Vec<usize> = Vec::new();
+        let mut col = 0;
+
+        while col < variables && rank < self.rows {
+            let block_end = (col + K).min(variables);
+            let first_pivot = rank;
+            let mut block_pivots: Vec<usize> = Vec::new();
+
+            // bits of the block columns of every row below the pivots, kept reduced by the block pivots
+            let block_cols: Vec<usize> = (col..block_end).collect();
+            let mut strips: Vec<usize> = (rank..self.rows).map(|row| self.bits(row, &block_cols)).collect();
+
+            for (bit, pivot_col) in block_cols.iter().enumerate() {
+                let found = (rank - first_pivot..strips.len()).find(|index| strips[*index] >> bit & 1 == 1);
+                let index = match found {
+                    Some(index) => index,
+                    None => continue,
+                };
+
+                let pivot_row = first_pivot + index;
+                let position = rank - first_pivot;
+                self.swap_rows(pivot_row, rank);
+                strips.swap(index, position);
+
+                // reduce the new pivot row by the previous block pivots and them by it
+                for (previous, previous_col) in block_pivots.iter().enumerate() {
+                    if self.get(rank, *previous_col) {
+                        self.add_row(rank, first_pivot + previous, col / 64)
+                    }
+                }
+                for previous in 0..block_pivots.len() {
+                    if self.get(first_pivot + previous, *pivot_col) {
+                        self.add_row(first_pivot + previous, rank, col / 64)
+                    }
+                }
+
+                let strip = self.bits(rank, &block_cols);
+                strips[position] = strip;
+                for other in strips.iter_mut().skip(position + 1) {
+                    if *other >> bit & 1 == 1 {
+                        *other ^= strip
+                    }
+                }
+
+                block_pivots.push(*pivot_col);
+                rank += 1;
+            }
+
+            if !block_pivots.is_empty() {
+                self.apply_table(first_pivot, &block_pivots, col / 64, threads);
+                pivots.extend(&block_pivots);
+            }
+
+            col = block_end;
+        }
+
+        let consistent = (rank..self.rows).all(|row| !self.get(row, variables));
+
+        DenseElimination { matrix: self, rank, pivots, consistent }
+    }
+
+    /// Eliminates `pivot_cols` from every row except the pivot rows themselves
+    /// (`first_pivot..first_pivot + pivot_cols.len()`) with a table of all their combinations
+    fn apply_table(&mut self, first_pivot: usize, pivot_cols: &[usize], from_word: usize, threads: usize) {
+        let words = self.words;
+        let width = words - from_word;
+        let pivot_rows = first_pivot..first_pivot + pivot_cols.len();
+
+        let mut table = vec![0u64; (1 << pivot_cols.len()) * width];
+        for mask in 1..1usize << pivot_cols.len() {
+            let lowest = mask.trailing_zeros() as usize;
+            let rest = mask & (mask - 1);
+            let source = &self.row(first_pivot + lowest)[from_word..];
+            for word in 0..width {
+                table[mask * width + word] = table[rest * width + word] ^ source[word]
+            }
+        }
+
+        let masks: Vec<usize> = (0..self.rows)
+            .map(|row| if pivot_rows.contains(&row) { 0 } else { self.bits(row, pivot_cols) })
+            .collect();
+
+        let rows_per_thread = self.rows.div_ceil(threads).max(1);
+        let table = &table;
+
+        thread::scope(|scope| {
+            for (data, masks) in self.data.chunks_mut(rows_per_thread * words).zip(masks.chunks(rows_per_thread)) {
+                scope.spawn(move || {
+                    for block in (from_word..words).step_by(WORDS_PER_BLOCK) {
+                        let block_end = (block + WORDS_PER_BLOCK).min(words);
+                        for (row, mask) in masks.iter().enumerate() {
+                            if *mask == 0 {
+                                continue;
+                            }
+                            let entry = &table[mask * width..(mask + 1) * width];
+                            let target = &mut data[row * words..(row + 1) * words];
+                            for word in block..block_end {
+                                target[word] ^= entry[word - from_word]
+                            }
+                        }
+                    }
+                });
+            }
+        });
+    }
+}
+
+impl DenseElimination {
+    /// Solution with every free variable set to 0
+    pub fn witness(&self) -> Option<Vec<bool>> {
+        if !self.consistent {
+            return None;
+        }
+
+        let constant = self.matrix.cols - 1;
+        let mut solution = vec![false; constant];
+        for (row, pivot) in self.pivots.iter().enumerate() {
+            solution[*pivot] = self.matrix.get(row, constant)
+        }
+
+        Some(solution)
+    }
+
+    /// Exact number of solutions in decimal
+    pub fn count(&self) -> String {
+        if !self.consistent {
+            return "0".to_owned();
+        }
+
+        power_of_two(self.matrix.cols - 1 - self.rank)
+    }
+}
+
+/// Number of threads to use when not told otherwise
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1)
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use crate::explain::unsat_core;
+use crate::report::string;
+use crate::{Expression, System, Value};
+
+const FORCED_ON: &str = "palegreen";
+const FORCED_OFF: &str = "lightgray";
+const FREE: &str = "white";
+const CORE: &str = "red";
+
+/// Graphviz DOT of the bipartite graph between switches (ellipses `s<N>`) and the scripts they
+/// toggle (boxes `c<N>`), one cluster per connected component. Switches forced on are green,
+/// forced off gray and free white. Without a solution, the scripts and assumptions of an
+/// inclusion-minimal unsat core are drawn red instead.
+pub fn to_dot(system: &System) -> String {
+    let scripts = system.scripts();
+    let elimination = system.elimination();
+    let backbone = elimination.backbone();
+    let core = if elimination.is_sat() { Vec::new() } else { unsat_core(system).unwrap_or_default() };
+
+    // switches take ids 0..switches, scripts follow
+    let mut components = Components::new(system.switches + scripts);
+    let mut edges: Vec<(i32, usize)> = Vec::new();
+    for script in 0..scripts {
+        for switch in switches(&system.equations[script].left) {
+            components.union(switch as usize - 1, system.switches + script);
+            edges.push((switch, script + 1));
+        }
+    }
+
+    // switches pinned by an assumption from the core
+    let core_switches: Vec<i32> = core
+        .iter()
+        .filter(|index| **index >= scripts)
+        .flat_map(|index| switches(&system.equations[*index].left))
+        .collect();
+
+    // nodes of every component, in the order the components first show up
+    let mut clusters: Vec<Vec<String>> = Vec::new();
+    let mut cluster_of: HashMap<usize, usize> = HashMap::new();
+    let mut add = |id: usize, node: String| {
+        let root = components.find(id);
+        let cluster = *cluster_of.entry(root).or_insert_with(|| {
+            clusters.push(Vec::new());
+            clusters.len() - 1
+        });
+        clusters[cluster].push(node);
+    };
+
+    for switch in 1..=system.switches as i32 {
+        let fill = match backbone.iter().find(|(s, _)| *s == switch) {
+            Some((_, true)) => FORCED_ON,
+            Some((_, false)) => FORCED_OFF,
+            None => FREE,
+        };
+        let highlight = if core_switches.contains(&switch) { format!(", color={}, penwidth=3", CORE) } else { String::new() };
+        add(
+            switch as usize - 1,
+            format!("s{} [label={}, fillcolor={}{}];", switch, string(&system.names.switch(switch)), fill, highlight),
+        );
+    }
+    for script in 1..=scripts {
+        let highlight = if core.contains(&(script - 1)) { format!(", color={}, penwidth=3", CORE) } else { String::new() };
+        add(
+            system.switches + script - 1,
+            format!("c{} [label={}, shape=box{}];", script, string(&system.names.script(script)), highlight),
+        );
+    }
+
+    let mut lines = vec![
+        "graph konfiguracni_retezec {".to_owned(),
+        "  node [style=filled, fillcolor=white];".to_owned(),
+    ];
+    for (index, nodes) in clusters.iter().enumerate() {
+        lines.push(format!("  subgraph cluster_{} {{", index + 1));
+        lines.push(format!("    label=\"component {}\";", index + 1));
+        lines.extend(nodes.iter().map(|node| format!("    {}", node)));
+        lines.push("  }".to_owned());
+    }
+    for (switch, script) in edges {
+        let core_edge = core.contains(&(script - 1));
+        lines.push(format!("  s{} -- c{}{};", switch, script, if core_edge { format!(" [color={}]", CORE) } else { String::new() }));
+    }
+    lines.push("}".to_owned());
+
+    lines.join("\n")
+}
+
+fn switches(expression: &Expression) -> Vec<i32> {
+    expression.values().iter().filter_map(|value| if let Value::Var(var) = value { Some(*var) } else { None }).collect()
+}
+
+/// Union-find over node ids
+struct Components {
+    parents: Vec<usize>,
+}
+
+impl Components {
+    fn new(nodes: usize) -> Self {
+        Self { parents: (0..nodes).collect() }
+    }
+
+    fn find(&mut self, node: usize) -> usize {
+        let mut root = node;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        self.parents[node] = root;
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parents[a] = b;
+    }
+}
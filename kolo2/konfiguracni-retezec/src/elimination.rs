@@ -0,0 +1,311 @@
+use crate::{Equation, Value};
+
+/// Row of the system over GF(2): XOR of the set coefficients equals `constant`.
+/// `origin` remembers which input equations were added together to get it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Row {
+    pub coefficients: Vec<u64>,
+    pub constant: bool,
+    pub origin: Vec<u64>,
+}
+
+impl Row {
+    fn new(variables: usize, equations: usize) -> Self {
+        Self {
+            coefficients: vec![0; words(variables)],
+            constant: false,
+            origin: vec![0; words(equations)],
+        }
+    }
+
+    /// Builds the row of `equation`, the `index`-th equation of the system.
+    /// Panics if the equation uses a switch outside of `1..=variables`
+    pub fn from_equation(equation: &Equation, index: usize, variables: usize, equations: usize) -> Self {
+        let mut row = Self::new(variables, equations);
+        toggle(&mut row.origin, index);
+
+        for expression in [&equation.left, &equation.right] {
+            for val in expression.values() {
+                match val {
+                    Value::True => row.constant ^= true,
+                    Value::False => {}
+                    Value::Var(var) => {
+                        assert!(
+                            (1..=variables as i32).contains(var),
+                            "switch {} is not one of the {} switches", var, variables
+                        );
+                        toggle(&mut row.coefficients, *var as usize - 1)
+                    }
+                }
+            }
+        }
+
+        row
+    }
+
+    pub fn add(&mut self, other: &Row) {
+        for (a, b) in self.coefficients.iter_mut().zip(&other.coefficients) {
+            *a ^= b
+        }
+        for (a, b) in self.origin.iter_mut().zip(&other.origin) {
+            *a ^= b
+        }
+        self.constant ^= other.constant;
+    }
+
+    pub fn has(&self, var: usize) -> bool {
+        get(&self.coefficients, var)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.iter().all(|word| *word == 0)
+    }
+
+    /// Variables (numbered from 1) with a set coefficient
+    pub fn variables(&self) -> Vec<i32> {
+        ones(&self.coefficients).into_iter().map(|var| var as i32 + 1).collect()
+    }
+
+    fn first_var(&self) -> Option<usize> {
+        first_set(&self.coefficients)
+    }
+
+    /// Indices of the input equations this row was combined from
+    pub fn origin(&self) -> Vec<usize> {
+        ones(&self.origin)
+    }
+}
+
+/// Gauss-Jordan elimination of the whole system, kept in reduced row echelon form
+#[derive(Debug, Clone)]
+pub struct Elimination {
+    pub variables: usize,
+    /// reduced rows, `rows[i]` has its pivot at `pivots[i]`
+    pub rows: Vec<Row>,
+    pub pivots: Vec<usize>,
+    /// first row reduced to `0 = 1`, if there is one
+    pub conflict: Option<Row>,
+}
+
+impl Elimination {
+    pub fn new(variables: usize, equations: &[Equation]) -> Self {
+        let mut elimination = Self {
+            variables,
+            rows: Vec::new(),
+            pivots: Vec::new(),
+            conflict: None,
+        };
+
+        for (index, equation) in equations.iter().enumerate() {
+            elimination.insert(Row::from_equation(equation, index, variables, equations.len()));
+        }
+
+        elimination
+    }
+
+    /// Reduces `row` by the current pivots and adds it as a new pivot row, if anything is left
+    pub fn insert(&mut self, mut row: Row) {
+        self.reduce(&mut row);
+
+        let pivot = match row.first_var() {
+            Some(pivot) => pivot,
+            None => {
+                if row.constant && self.conflict.is_none() {
+                    self.conflict = Some(row);
+                }
+                return;
+            }
+        };
+
+        for existing in self.rows.iter_mut() {
+            if existing.has(pivot) {
+                existing.add(&row)
+            }
+        }
+
+        self.rows.push(row);
+        self.pivots.push(pivot);
+    }
+
+    /// Eliminates every pivot variable from `row`
+    pub fn reduce(&self, row: &mut Row) {
+        for (existing, pivot) in self.rows.iter().zip(&self.pivots) {
+            if row.has(*pivot) {
+                row.add(existing)
+            }
+        }
+    }
+
+    pub fn is_sat(&self) -> bool {
+        self.conflict.is_none()
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Variables (numbered from 1) that are not pivots and can be chosen freely
+    pub fn free_variables(&self) -> Vec<i32> {
+        let mut is_pivot = vec![false; self.variables];
+        for pivot in &self.pivots {
+            is_pivot[*pivot] = true
+        }
+
+        (0..self.variables)
+            .filter(|var| !is_pivot[*var])
+            .map(|var| var as i32 + 1)
+            .collect()
+    }
+
+    /// Variables with the same value in every solution, sorted by variable
+    pub fn backbone(&self) -> Vec<(i32, bool)> {
+        if !self.is_sat() {
+            return Vec::new();
+        }
+
+        let mut backbone: Vec<(i32, bool)> = self
+            .rows
+            .iter()
+            .zip(&self.pivots)
+            .filter(|(row, _)| row.coefficients.iter().map(|word| word.count_ones()).sum::<u32>() == 1)
+            .map(|(row, pivot)| (*pivot as i32 + 1, row.constant))
+            .collect();
+
+        backbone.sort();
+        backbone
+    }
+
+    /// Solution with every free variable set to 0
+    pub fn witness(&self) -> Option<Vec<bool>> {
+        if !self.is_sat() {
+            return None;
+        }
+
+        let mut solution = vec![false; self.variables];
+        for (row, pivot) in self.rows.iter().zip(&self.pivots) {
+            solution[*pivot] = row.constant
+        }
+
+        Some(solution)
+    }
+
+    /// Exact number of solutions in decimal
+    pub fn count(&self) -> String {
+        if !self.is_sat() {
+            return "0".to_owned();
+        }
+
+        power_of_two(self.variables - self.rank())
+    }
+}
+
+/// Iterator over every solution of the system, see `System::solutions`.
+/// Free variables are counted up in binary, first free variable being the lowest bit.
+#[derive(Debug, Clone)]
+pub struct Solutions {
+    elimination: Elimination,
+    free: Vec<usize>,
+    /// values of the free variables for the next solution, `None` when done
+    next: Option<Vec<bool>>,
+}
+
+impl Solutions {
+    pub fn new(elimination: Elimination) -> Self {
+        let free: Vec<usize> = elimination.free_variables().iter().map(|var| *var as usize - 1).collect();
+        let next = if elimination.is_sat() { Some(vec![false; free.len()]) } else { None };
+
+        Self { elimination, free, next }
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Vec<bool>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let values = self.next.take()?;
+
+        let mut solution = vec![false; self.elimination.variables];
+        for (var, value) in self.free.iter().zip(&values) {
+            solution[*var] = *value
+        }
+        for (row, pivot) in self.elimination.rows.iter().zip(&self.elimination.pivots) {
+            let mut value = row.constant;
+            for var in &self.free {
+                if row.has(*var) {
+                    value ^= solution[*var]
+                }
+            }
+            solution[*pivot] = value
+        }
+
+        // binary increment, done after overflow
+        let mut values = values;
+        if let Some(position) = values.iter().position(|value| !value) {
+            for value in values.iter_mut().take(position) {
+                *value = false
+            }
+            values[position] = true;
+            self.next = Some(values);
+        }
+
+        Some(solution)
+    }
+}
+
+/// 2^exponent in decimal, it doesn't fit any integer type for bigger systems
+pub fn power_of_two(exponent: usize) -> String {
+    // little endian digits in base 10^9
+    let mut digits: Vec<u64> = vec![1];
+
+    for _ in 0..exponent {
+        let mut carry = 0;
+        for digit in digits.iter_mut() {
+            let value = *digit * 2 + carry;
+            *digit = value % 1_000_000_000;
+            carry = value / 1_000_000_000;
+        }
+        if carry > 0 {
+            digits.push(carry)
+        }
+    }
+
+    let mut result = digits.pop().unwrap().to_string();
+    for digit in digits.iter().rev() {
+        result.push_str(&format!("{:09}", digit))
+    }
+
+    result
+}
+
+fn words(bits: usize) -> usize {
+    bits.div_ceil(64)
+}
+
+fn get(bits: &[u64], index: usize) -> bool {
+    bits[index / 64] >> (index % 64) & 1 == 1
+}
+
+fn toggle(bits: &mut [u64], index: usize) {
+    bits[index / 64] ^= 1 << (index % 64)
+}
+
+fn first_set(bits: &[u64]) -> Option<usize> {
+    bits.iter()
+        .enumerate()
+        .find(|(_, word)| **word != 0)
+        .map(|(index, word)| index * 64 + word.trailing_zeros() as usize)
+}
+
+fn ones(bits: &[u64]) -> Vec<usize> {
+    let mut result = Vec::new();
+
+    for (index, word) in bits.iter().enumerate() {
+        let mut word = *word;
+        while word != 0 {
+            result.push(index * 64 + word.trailing_zeros() as usize);
+            word &= word - 1;
+        }
+    }
+
+    result
+}
@@ -0,0 +1,106 @@
+use crate::elimination::{Elimination, Row};
+use crate::Expression::{Val, Xor};
+use crate::Value::{False, True, Var};
+use crate::{Equation, System};
+
+/// Scripts whose equations add up to `i_var = value`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Explanation {
+    pub var: i32,
+    pub value: bool,
+    /// equations (indices into `System::equations`) in input order
+    pub scripts: Vec<usize>,
+}
+
+/// Finds an inclusion-minimal set of scripts forcing switch `var`, errors if it isn't forced
+pub fn explain(system: &System, var: i32) -> Result<Explanation, String> {
+    if var < 1 || var as usize > system.switches {
+        return Err(format!("switch {} doesn't exist", var));
+    }
+
+    let elimination = system.elimination();
+    if !elimination.is_sat() {
+        return Err("system has no solution, so every switch is forced".to_owned());
+    }
+
+    let value = match elimination.backbone().iter().find(|(switch, _)| *switch == var) {
+        Some((_, value)) => *value,
+        None => return Err(format!("{} is not forced", system.names.switch(var))),
+    };
+
+    let forced = Equation { left: Val(Var(var)), right: Val(if value { True } else { False }) };
+    let pivot_row = elimination.pivots.iter().position(|pivot| *pivot as i32 == var - 1).unwrap();
+    let mut scripts = elimination.rows[pivot_row].origin();
+
+    // while the scripts are linearly dependent, some of them can be left out
+    for script in scripts.clone() {
+        let without: Vec<usize> = scripts.iter().copied().filter(|s| *s != script).collect();
+        if follows(system, &without, &forced) {
+            scripts = without;
+        }
+    }
+
+    Ok(Explanation { var, value, scripts })
+}
+
+/// Inclusion-minimal set of equations (scripts and assumptions) with no solution,
+/// `None` if the whole system has a solution
+pub fn unsat_core(system: &System) -> Option<Vec<usize>> {
+    let mut core = system.elimination().conflict?.origin();
+
+    for index in core.clone() {
+        let without: Vec<usize> = core.iter().copied().filter(|i| *i != index).collect();
+        if !subsystem(system, &without).is_sat() {
+            core = without;
+        }
+    }
+
+    Some(core)
+}
+
+/// Whether `equation` is a linear combination of the given equations of the system
+fn follows(system: &System, scripts: &[usize], equation: &Equation) -> bool {
+    let elimination = subsystem(system, scripts);
+
+    let mut row = Row::from_equation(equation, scripts.len(), system.switches, scripts.len() + 1);
+    elimination.reduce(&mut row);
+
+    row.is_zero() && !row.constant
+}
+
+fn subsystem(system: &System, scripts: &[usize]) -> Elimination {
+    let equations: Vec<Equation> = scripts.iter().map(|script| system.equations[*script].clone()).collect();
+    Elimination::new(system.switches, &equations)
+}
+
+impl Explanation {
+    /// Adds the scripts one by one, showing the running sum after each of them:
+    /// ```text
+    ///   script 1: i_1 XOR i_3 = 1
+    /// + script 2: i_1 XOR i_2 = 1
+    ///   = i_2 XOR i_3 = 0
+    /// ```
+    pub fn to_text(&self, system: &System) -> String {
+        let names = &system.names;
+        let mut lines = vec![format!(
+            "{} follows from {} script(s):",
+            Equation { left: Val(Var(self.var)), right: Val(if self.value { True } else { False }) }.named(names),
+            self.scripts.len()
+        )];
+
+        let mut sum = Row::from_equation(&Equation { left: Xor(Vec::new()), right: Val(False) }, 0, system.switches, 1);
+        for (step, script) in self.scripts.iter().enumerate() {
+            let equation = &system.equations[*script];
+            lines.push(format!("{} {}", if step == 0 { " " } else { "+" }, system.describe(*script)));
+
+            sum.add(&Row::from_equation(equation, 0, system.switches, 1));
+            if step > 0 {
+                let vars: Vec<_> = sum.variables().into_iter().map(Var).collect();
+                let running = Equation { left: Xor(vars), right: Val(if sum.constant { True } else { False }) };
+                lines.push(format!("  = {}", running.named(names)));
+            }
+        }
+
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{Equation, Expression, System, Value};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Topology {
+    Rectangle,
+    Torus,
+}
+
+/// Lights-Out style panel: pressing a cell toggles every cell covered by `mask`
+/// centered on it.
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub rows: usize,
+    pub cols: usize,
+    pub topology: Topology,
+    /// (dy, dx) offsets relative to the pressed cell
+    pub mask: Vec<(i32, i32)>,
+    /// initial state, row by row, `true` = lit
+    pub pattern: Vec<bool>,
+}
+
+impl Grid {
+    /// Parses the grid description:
+    /// ```text
+    /// R C rect|torus
+    /// MH MW
+    /// <MH lines of the mask, '#' = toggled, '.' = untouched>
+    /// <R lines of the initial pattern, '#' = lit, '.' = dark>
+    /// ```
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+
+        let header: Vec<&str> = lines.next().ok_or("missing grid header")?.split_whitespace().collect();
+        if header.len() != 3 {
+            return Err("grid header has to be \"R C rect|torus\"".to_owned());
+        }
+        let rows: usize = header[0].parse().map_err(|_| "failed to parse grid rows")?;
+        let cols: usize = header[1].parse().map_err(|_| "failed to parse grid cols")?;
+        let topology = match header[2] {
+            "rect" => Topology::Rectangle,
+            "torus" => Topology::Torus,
+            other => return Err(format!("unknown topology \"{}\"", other)),
+        };
+        if rows == 0 || cols == 0 {
+            return Err("grid has to have at least one cell".to_owned());
+        }
+
+        let mask_header: Vec<&str> = lines.next().ok_or("missing mask header")?.split_whitespace().collect();
+        if mask_header.len() != 2 {
+            return Err("mask header has to be \"MH MW\"".to_owned());
+        }
+        let mask_h: usize = mask_header[0].parse().map_err(|_| "failed to parse mask height")?;
+        let mask_w: usize = mask_header[1].parse().map_err(|_| "failed to parse mask width")?;
+        if mask_h.is_multiple_of(2) || mask_w.is_multiple_of(2) {
+            return Err("mask dimensions have to be odd so it has a center".to_owned());
+        }
+
+        let mut mask = Vec::new();
+        for dy in 0..mask_h {
+            let cells = parse_row(lines.next().ok_or("mask is too short")?, mask_w)?;
+            for (dx, on) in cells.into_iter().enumerate() {
+                if on {
+                    mask.push((dy as i32 - (mask_h / 2) as i32, dx as i32 - (mask_w / 2) as i32));
+                }
+            }
+        }
+
+        let mut pattern = Vec::with_capacity(rows * cols);
+        for _ in 0..rows {
+            pattern.extend(parse_row(lines.next().ok_or("pattern is too short")?, cols)?);
+        }
+
+        Ok(Self { rows, cols, topology, mask, pattern })
+    }
+
+    /// Variable (press of a cell) of cell at `row`, `col`, numbered from 1 like switches
+    pub fn var(&self, row: usize, col: usize) -> i32 {
+        (row * self.cols + col) as i32 + 1
+    }
+
+    /// System with one switch per cell press, see `equations`
+    pub fn system(&self) -> System {
+        System { switches: self.rows * self.cols, equations: self.equations(), ..System::default() }
+    }
+
+    /// Cells toggled by pressing cell at `row`, `col`
+    fn neighbourhood(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+
+        for (dy, dx) in &self.mask {
+            let y = row as i32 + dy;
+            let x = col as i32 + dx;
+
+            match self.topology {
+                Topology::Rectangle => {
+                    if y >= 0 && x >= 0 && (y as usize) < self.rows && (x as usize) < self.cols {
+                        cells.push((y as usize, x as usize))
+                    }
+                }
+                Topology::Torus => {
+                    cells.push((y.rem_euclid(self.rows as i32) as usize, x.rem_euclid(self.cols as i32) as usize))
+                }
+            }
+        }
+
+        cells
+    }
+
+    /// One equation per cell: XOR of presses toggling it has to equal its initial state,
+    /// so that every cell ends up dark. A mask wrapping around a torus can cover one cell
+    /// several times, those toggles cancel out in pairs
+    pub fn equations(&self) -> Vec<Equation> {
+        let mut lefts: Vec<Vec<Value>> = vec![Vec::new(); self.rows * self.cols];
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let var = Value::Var(self.var(row, col));
+                for (y, x) in self.neighbourhood(row, col) {
+                    let left = &mut lefts[y * self.cols + x];
+                    match left.iter().position(|value| *value == var) {
+                        Some(index) => {
+                            left.swap_remove(index);
+                        }
+                        None => left.push(var.clone()),
+                    }
+                }
+            }
+        }
+
+        lefts
+            .into_iter()
+            .zip(&self.pattern)
+            .map(|(left, lit)| Equation {
+                left: Expression::Xor(left),
+                right: Expression::Val(if *lit { Value::True } else { Value::False }),
+            })
+            .collect()
+    }
+
+    /// Renders presses given as the solver's bit string back as a grid
+    pub fn render(&self, solution: &str) -> String {
+        let bits: Vec<char> = solution.chars().collect();
+        let mut result = String::new();
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                result.push(if bits[row * self.cols + col] == '1' { '#' } else { '.' });
+            }
+            result.push('\n');
+        }
+
+        result.trim_end().to_owned()
+    }
+}
+
+fn parse_row(line: &str, width: usize) -> Result<Vec<bool>, String> {
+    let cells: Vec<bool> = line
+        .trim()
+        .chars()
+        .map(|ch| match ch {
+            '#' | '1' => Ok(true),
+            '.' | '0' => Ok(false),
+            other => Err(format!("unexpected character '{}' in grid", other)),
+        })
+        .collect::<Result<_, _>>()?;
+
+    if cells.len() != width {
+        return Err(format!("row \"{}\" has to be {} cells wide", line.trim(), width));
+    }
+
+    Ok(cells)
+}
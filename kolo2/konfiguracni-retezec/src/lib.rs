@@ -0,0 +1,847 @@
+//! Solver for systems of XOR equations over switches ("konfiguracni retezec").
+//!
+//! ```
+//! use konfiguracni_retezec::{Budget, System};
+//!
+//! let mut system = System::new(3);
+//! system.add_script(&[1, 2, 3]).add_script(&[2, 3]);
+//!
+//! assert_eq!(system.count(), "2");
+//! assert_eq!(system.search(&mut Budget::unlimited()), Some((2, "001".to_owned())));
+//! assert_eq!(system.solutions().count(), 2);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+use std::fmt::{Display, Formatter};
+use crate::Expression::{Val, Xor};
+use crate::Value::{True, False, Var};
+
+pub use crate::budget::Budget;
+pub use crate::dense::{DenseElimination, DenseMatrix};
+pub use crate::elimination::{Elimination, Solutions};
+pub use crate::names::Names;
+pub use crate::project::Projection;
+pub use crate::report::Report;
+
+pub mod batch;
+pub mod budget;
+pub mod check;
+pub mod dense;
+pub mod dot;
+pub mod elimination;
+pub mod explain;
+pub mod grid;
+pub mod names;
+pub mod oracle;
+pub mod proof;
+pub mod project;
+pub mod random;
+pub mod report;
+pub mod witness;
+
+/// `left = right` over GF(2)
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct Equation {
+    pub left: Expression,
+    pub right: Expression,
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum Expression {
+    Val(Value),
+    /// XOR of all the values, empty XOR is 0
+    Xor(Vec<Value>),
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum Value {
+    True,
+    False,
+    /// switch, numbered from 1
+    Var(i32),
+}
+
+/// System of equations over switches numbered from 1 to `switches`
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct System {
+    pub switches: usize,
+    pub equations: Vec<Equation>,
+    pub names: Names,
+    /// the last `assumptions` equations are assumptions (`i_N = c`), not scripts
+    pub assumptions: usize,
+}
+
+impl System {
+    pub fn new(switches: usize) -> Self {
+        Self { switches, equations: Vec::new(), names: Names::default(), assumptions: 0 }
+    }
+
+    /// Adds a script, it runs when odd number of the given switches is on
+    /// and every script has to run
+    pub fn add_script(&mut self, switches: &[i32]) -> &mut Self {
+        self.add_equation(Equation {
+            left: Xor(switches.iter().map(|switch| Var(*switch)).collect()),
+            right: Val(True),
+        })
+    }
+
+    pub fn add_equation(&mut self, equation: Equation) -> &mut Self {
+        assert_eq!(self.assumptions, 0, "equations can't be added after assumptions");
+        self.equations.push(equation);
+        self
+    }
+
+    /// Pins `switch` to `value`, assumptions stay after every script
+    pub fn assume(&mut self, switch: i32, value: bool) -> &mut Self {
+        self.equations.push(Equation {
+            left: Val(Var(switch)),
+            right: Val(if value { True } else { False }),
+        });
+        self.assumptions += 1;
+        self
+    }
+
+    /// Parses and adds assumptions like `3=1,9=1,fan=0`
+    pub fn assume_all(&mut self, assumptions: &str) -> Result<&mut Self, String> {
+        for assumption in assumptions.split(',').filter(|assumption| !assumption.trim().is_empty()) {
+            let (switch, value) = match assumption.split_once('=') {
+                Some((switch, value)) => (switch.trim(), value.trim()),
+                None => return Err(format!("assumption \"{}\" has to be <switch>=0|1", assumption)),
+            };
+
+            let switch = self.switch(switch)?;
+            let value = match value {
+                "0" => false,
+                "1" => true,
+                _ => return Err(format!("assumption \"{}\" has to be <switch>=0|1", assumption)),
+            };
+
+            self.assume(switch, value);
+        }
+
+        Ok(self)
+    }
+
+    /// Number of scripts, equations that aren't assumptions
+    pub fn scripts(&self) -> usize {
+        self.equations.len() - self.assumptions
+    }
+
+    /// Switch given by its number or name
+    pub fn switch(&self, switch: &str) -> Result<i32, String> {
+        let number = match switch.parse::<i32>() {
+            Ok(number) => number,
+            Err(_) => match self.names.switches.iter().find(|(_, name)| name.as_str() == switch) {
+                Some((number, _)) => *number,
+                None => return Err(format!("unknown switch \"{}\"", switch)),
+            }
+        };
+
+        if number < 1 || number as usize > self.switches {
+            return Err(format!("switch {} doesn't exist", number));
+        }
+
+        Ok(number)
+    }
+
+    /// Parses a list of switches like `1,2,5-9,fan`, each switch once in the order given
+    pub fn switch_list(&self, list: &str) -> Result<Vec<i32>, String> {
+        let mut switches: Vec<i32> = Vec::new();
+
+        for item in list.split(',').map(|item| item.trim()).filter(|item| !item.is_empty()) {
+            let range = match item.split_once('-') {
+                // names can contain '-' too
+                Some((first, last)) if self.switch(item).is_err() => {
+                    let (first, last) = (self.switch(first.trim())?, self.switch(last.trim())?);
+                    if first > last {
+                        return Err(format!("range \"{}\" is empty", item));
+                    }
+                    first..=last
+                }
+                _ => {
+                    let switch = self.switch(item)?;
+                    switch..=switch
+                }
+            };
+
+            for switch in range {
+                if !switches.contains(&switch) {
+                    switches.push(switch);
+                }
+            }
+        }
+
+        Ok(switches)
+    }
+
+    /// Describes equation `index`, `script N: ...` or `assume ...`
+    pub fn describe(&self, index: usize) -> String {
+        if index < self.scripts() {
+            format!("script {}: {}", self.names.script(index + 1), self.equations[index].named(&self.names))
+        } else {
+            format!("assume {}", self.equations[index].named(&self.names))
+        }
+    }
+
+    /// Parses the `N M` format, one equation per script in script order.
+    /// Switch lines can be followed by a line `names` and the symbol table, see `Names::parse`.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        Self::read(contents.as_bytes())
+    }
+
+    /// Same as `parse`, but reads the input line by line in a single pass, keeping only the scripts
+    /// built so far (and the symbol table) in memory
+    pub fn read(mut input: impl BufRead) -> Result<Self, String> {
+        let mut line = String::new();
+        let mut next_line = |line: &mut String| -> Result<bool, String> {
+            line.clear();
+            match input.read_line(line) {
+                Ok(read) => Ok(read > 0),
+                Err(e) => Err(format!("failed to read input: {}", e)),
+            }
+        };
+
+        next_line(&mut line)?;
+        let mut first_line = line.split_whitespace();
+
+        let vars: usize = match first_line.next().map(|s| s.parse()) {
+            Some(Ok(num)) => num,
+            _ => return Err("failed to parse first line".to_owned())
+        };
+
+        let scripts: i32 = match first_line.next().map(|s| s.parse()) {
+            Some(Ok(num)) => num,
+            _ => return Err("failed to parse first line".to_owned())
+        };
+
+        // switch line `i` lists the scripts switch `i` toggles, the first number is just their count
+        let mut lefts: Vec<Vec<Value>> = vec![Vec::new(); scripts.max(0) as usize];
+        let mut switch = 0;
+        let mut symbols: Vec<String> = Vec::new();
+        while next_line(&mut line)? {
+            if line.trim() == "names" {
+                while next_line(&mut line)? {
+                    symbols.push(line.clone());
+                }
+                break;
+            }
+
+            switch += 1;
+            if switch as usize > vars && !line.trim().is_empty() {
+                return Err(format!("switch line {} is beyond the {} switches", switch, vars));
+            }
+            for a in line.split_whitespace().skip(1).filter_map(|x| x.parse::<i32>().ok()) {
+                if (1..=scripts).contains(&a) {
+                    let left = &mut lefts[a as usize - 1];
+                    // listing a script twice doesn't toggle it back
+                    if left.last() != Some(&Var(switch)) {
+                        left.push(Var(switch));
+                    }
+                }
+            }
+        }
+
+        let symbols: Vec<&str> = symbols.iter().map(|line| line.as_str()).collect();
+        let mut system = Self::new(vars);
+        system.names = Names::parse(&symbols, system.switches, lefts.len())?;
+
+        for left in lefts {
+            system.add_equation(Equation {
+                left: Xor(left),
+                right: Val(True),
+            });
+        }
+
+        Ok(system)
+    }
+
+    /// All switches, `1..=switches`
+    pub fn variables(&self) -> Vec<i32> {
+        (1..=self.switches as i32).collect()
+    }
+
+    /// Solves the system with the search solver, returns number of solutions and the first one
+    /// (the first in lexicographic order), `None` if there is no solution or the budget ran out
+    /// before finding any.
+    pub fn search(&self, budget: &mut Budget) -> Option<(i32, String)> {
+        if self.equations.is_empty() {
+            return None;
+        }
+
+        let mut equations: Vec<Equation> = self.equations.iter().map(Equation::normalized).collect();
+        equations.sort_by_key(|eq| eq.count_members());
+
+        solve_equations(self.variables(), &equations, budget).ok()
+    }
+
+    /// Gauss-Jordan elimination of the system, gives rank, backbone, conflicts etc.
+    pub fn elimination(&self) -> Elimination {
+        Elimination::new(self.switches, &self.equations)
+    }
+
+    /// Word parallel elimination without provenance, much faster for big dense systems
+    pub fn dense(&self, threads: usize) -> DenseElimination {
+        DenseMatrix::from_system(self).eliminate(threads)
+    }
+
+    /// Exact number of solutions in decimal
+    pub fn count(&self) -> String {
+        self.elimination().count()
+    }
+
+    /// Iterates over every solution, `solution[i]` is the value of switch `i + 1`
+    pub fn solutions(&self) -> Solutions {
+        Solutions::new(self.elimination())
+    }
+
+    pub fn report(&self) -> Report {
+        Report::new(self)
+    }
+}
+
+impl Equation {
+    /// Whether the equation holds when switch `i` has value `solution[i - 1]`
+    pub fn holds(&self, solution: &[bool]) -> bool {
+        let value = |expression: &Expression| -> bool {
+            expression.values().iter().fold(false, |result, val| result ^ match val {
+                True => true,
+                False => false,
+                Var(var) => solution[*var as usize - 1]
+            })
+        };
+
+        value(&self.left) == value(&self.right)
+    }
+
+    /// Same as `Display`, but with switch names
+    pub fn named(&self, names: &Names) -> String {
+        format!("{} = {}", self.left.named(names), self.right.named(names))
+    }
+
+    /// The same equation as `i_a XOR i_b ... = c`, each switch at most once. The search solver
+    /// substitutes for every copy of a switch, so a switch listed twice has to cancel out first
+    fn normalized(&self) -> Self {
+        let mut vars: Vec<i32> = Vec::new();
+        let mut constant = false;
+        for expression in [&self.left, &self.right] {
+            for val in expression.values() {
+                match val {
+                    True => constant ^= true,
+                    False => {}
+                    Var(var) => match vars.iter().position(|other| other == var) {
+                        Some(index) => {
+                            vars.remove(index);
+                        }
+                        None => vars.push(*var)
+                    }
+                }
+            }
+        }
+
+        Equation {
+            left: Xor(vars.into_iter().map(Var).collect()),
+            right: Val(if constant { True } else { False }),
+        }
+    }
+
+    fn count_members(&self) -> usize {
+        let mut counter = 0;
+
+        match &self.left {
+            Val(_) => counter += 1,
+            Xor(vals) => counter += vals.len()
+        }
+
+        match &self.right {
+            Val(_) => counter += 1,
+            Xor(vals) => counter += vals.len()
+        }
+
+        counter
+    }
+
+    fn has_var(&self, var: &i32) -> Result<bool, ()> {
+        match &self.left {
+            Val(val) => {
+                if let Var(v) = val {
+                    if var == v { return Ok(true); }
+                }
+            }
+            Xor(vals) => {
+                for val in vals {
+                    if let Var(v) = val {
+                        if var == v { return Ok(true); }
+                    }
+                }
+            }
+        }
+
+        match &self.right {
+            Val(val) => {
+                if let Var(v) = val {
+                    if var == v { return Ok(false); }
+                }
+            }
+            Xor(vals) => {
+                for val in vals {
+                    if let Var(v) = val {
+                        if var == v { return Ok(false); }
+                    }
+                }
+            }
+        }
+
+        Err(())
+    }
+
+    fn evaluate(&self, var: &i32, substitution_for: i32, substitution: Vec<Value>) -> Result<Self, ()> {
+        let side = match self.has_var(var) {
+            Ok(side) => side,
+            Err(_) => return Err(())
+        };
+
+        let mut vals: Vec<Value> = Vec::new();
+        match &self.left {
+            Val(val) => { if !side { vals.push(val.clone()) } }
+            Xor(vls) => {
+                for val in vls {
+                    if val != &Var(*var) { vals.push(val.clone()) }
+                }
+            }
+        }
+        match &self.right {
+            Val(val) => { if side { vals.push(val.clone()) } }
+            Xor(v) => {
+                for val in v {
+                    if val != &Var(*var) { vals.push(val.clone()) }
+                }
+            }
+        }
+
+        // substitute
+        let vals = if substitution_for >= 0 {
+            let mut sub = 0;
+            let mut new_vals: Vec<Value> = Vec::new();
+            for val in &vals {
+                if val == &Var(substitution_for) {
+                    sub += 1;
+                } else {
+                    new_vals.push(val.clone())
+                }
+            }
+            let mut vals = new_vals;
+            for _ in 0..sub {
+                vals.extend(substitution.clone())
+            }
+            vals
+        } else { vals };
+
+        let mut eq = Equation {
+            left: Val(Var(*var)),
+            right: if vals.len() == 1 {
+                Val(vals[0].clone())
+            } else {
+                Xor(vals)
+            },
+        };
+        match eq.solve_for_left() {
+            Ok(_) => Ok(eq),
+            Err(_) => Err(())
+        }
+    }
+
+    /// Returns Err if equation is unsolvable (same variable on both sides)
+    fn solve_for_left(&mut self) -> Result<(), ()> {
+        let looking_for_val = if let Val(val) = &self.left {
+            val
+        } else { panic!("Left side of equation has to be Val") };
+
+        let simplified_equation = match &self.right {
+            Val(val) => {
+                if val == looking_for_val {
+                    return Err(());
+                }
+                Val(val.clone())
+            }
+            Xor(expression) => {
+                let mut found_numbers: Vec<bool> = Vec::new();
+                let mut found_vars: HashSet<i32> = HashSet::new();
+
+                for val in expression {
+                    if val == looking_for_val { return Err(()); }
+                    match val {
+                        True => found_numbers.push(true),
+                        False => found_numbers.push(false),
+                        Var(var) => {
+                            if found_vars.contains(var) {
+                                found_vars.remove(var);
+                                found_numbers.push(false);
+                            } else { found_vars.insert(*var); }
+                        }
+                    }
+                }
+
+                let mut result = found_numbers.pop().expect("Expect at least one val in expression");
+                if !found_numbers.is_empty() {
+                    for num in found_numbers {
+                        result ^= num
+                    }
+                }
+
+                let mut vals: Vec<Value> = Vec::new();
+
+                vals.push(match result {
+                    true => True,
+                    false => False
+                });
+
+                for var in found_vars {
+                    vals.push(Var(var))
+                }
+
+                if vals.len() == 1 {
+                    Val(vals[0].clone())
+                } else {
+                    Xor(vals)
+                }
+            }
+        };
+        self.right = simplified_equation;
+
+        Ok(())
+    }
+
+    fn analyse(&self) -> Vec<i32> {
+        let mut vars: Vec<i32> = Vec::new();
+        match &self.left {
+            Val(val) => {
+                if let Var(var) = val { vars.push(*var) }
+            }
+            Xor(vals) => {
+                for val in vals {
+                    if let Var(var) = val { vars.push(*var) }
+                }
+            }
+        }
+        vars
+    }
+
+    fn check_equation_validity(&self, variables: &HashMap<i32, bool>) -> bool {
+        let mut vars: Vec<i32> = Vec::new();
+        let mut must_equal_to: Option<bool> = None;
+
+        // collect variables and "must_equal_to"
+        match &self.left {
+            Val(val) => {
+                match val {
+                    True => must_equal_to = Some(true),
+                    False => must_equal_to = Some(false),
+                    Var(var) => vars.push(*var)
+                }
+            }
+            Xor(expression) => {
+                for val in expression {
+                    match val {
+                        True => must_equal_to = Some(true),
+                        False => must_equal_to = Some(false),
+                        Var(var) => vars.push(*var)
+                    }
+                }
+            }
+        }
+
+        match &self.right {
+            Val(val) => {
+                match val {
+                    True => must_equal_to = Some(true),
+                    False => must_equal_to = Some(false),
+                    Var(var) => vars.push(*var)
+                }
+            }
+            Xor(expression) => {
+                for val in expression {
+                    match val {
+                        True => must_equal_to = Some(true),
+                        False => must_equal_to = Some(false),
+                        Var(var) => vars.push(*var)
+                    }
+                }
+            }
+        }
+
+        if must_equal_to.is_none() {
+            panic!("Something is very wrong")
+        }
+
+        // substitute variables
+        let mut substituted_vars: Vec<bool> = Vec::new();
+        for var in vars {
+            match variables.get(&var) {
+                None => return true,
+                Some(val) => substituted_vars.push(*val)
+            }
+        }
+
+        // no switch left (script no switch toggles) XORs to 0
+        let mut result = false;
+        for num in substituted_vars {
+            result ^= num
+        }
+
+        if Some(result) == must_equal_to {
+            return true;
+        }
+        false
+    }
+}
+
+fn is_in_extracted(extracted_equations: &HashMap<i32, Vec<Equation>>, analysis: &[i32], except: &i32) -> Result<(i32, Expression), ()> {
+    for eqs in extracted_equations.values() {
+        for eq in eqs {
+            if let Val(Var(i)) = eq.left {
+                if &i == except {
+                    continue
+                } else if analysis.contains(&i) {
+                    return Ok((i, eq.right.clone()))
+                }
+            } else { panic!("This shouldn't happen") }
+        }
+    }
+    Err(())
+}
+
+fn solve(
+    variables: Vec<i32>,
+    extracted_constants: Vec<Equation>,
+    mut extracted_equations: Vec<Equation>,
+    budget: &mut Budget
+) -> Result<(i32, String), ()> {
+    let mut vars: HashMap<i32, bool> = HashMap::new();
+    let mut solutions: Vec<Vec<bool>> = Vec::new();
+
+    for const_ in &extracted_constants {
+        if let Val(Var(left)) = const_.left {
+            match &const_.right {
+                Val(val) => {
+                    match val {
+                        True => {
+                            if let Some(v) = vars.get(&left) {
+                                if v == &false {
+                                    return Err(());
+                                }
+                            } else {
+                                vars.insert(left, true);
+                            }
+                        },
+                        False => {
+                            if let Some(v) = vars.get(&left) {
+                                if v == &true {
+                                    return Err(());
+                                }
+                            } else {
+                                vars.insert(left, false);
+                            }
+                        },
+                        Var(_) => panic!()
+                    };
+                }
+                Xor(_) => panic!()
+            }
+        } else { panic!("This shouldn't happen") }
+    }
+
+    extracted_equations.sort_by_key(|eq| {
+        if let Xor(values) = &eq.right {
+            values.len()
+        } else {
+            0
+        }
+    });
+
+
+    solve_recursion(vars, &variables, &mut solutions, &extracted_equations, budget);
+
+    let solutions_length = solutions.len() as i32;
+    if solutions_length == 0 {
+        return Err(());
+    }
+
+    Ok((solutions_length, bool_vec_to_string(&solutions[0])))
+}
+
+fn solve_recursion (
+    variables: HashMap<i32, bool>,
+    all_vars: &Vec<i32>,
+    solutions: &mut Vec<Vec<bool>>,
+    extracted_equations: &Vec<Equation>,
+    budget: &mut Budget
+) {
+    if !budget.spend() {
+        return;
+    }
+
+
+    let mut valid = true;
+    for eq in extracted_equations {
+        if !eq.check_equation_validity(&variables) {
+            valid = false;
+        }
+    }
+
+    if valid && variables.len() == all_vars.len() {
+        let mut solution: Vec<bool> = Vec::new();
+        for v in all_vars {
+            solution.push(*variables.get(v).unwrap())
+        }
+        solutions.push(solution);
+        return;
+    }
+    if variables.len() == all_vars.len() || !valid {
+        return;
+    }
+
+    // kvuli tomuhle to asi dlouho trva :((((
+    let mut current_var = -1;
+    for var in all_vars {
+        if variables.contains_key(var) {
+            continue
+        }
+        current_var = *var;
+        break;
+    }
+
+    if current_var == -1 {
+        panic!("This shouldn't happen")
+    }
+
+    let mut f = variables.clone();
+    let mut t = variables.clone();
+    f.insert(current_var, false);
+    t.insert(current_var, true);
+    solve_recursion(f, all_vars, solutions, extracted_equations, budget); // try new var with 0
+    solve_recursion(t, all_vars, solutions, extracted_equations, budget); // try new var with 1
+}
+
+
+
+/// Extracts constants and equations from every equation of the system and solves it
+fn solve_equations(variables: Vec<i32>, equations: &[Equation], budget: &mut Budget) -> Result<(i32, String), ()> {
+    let mut extracted_constants: HashMap<i32, Vec<Equation>> = HashMap::new();
+    let mut extracted_equations: HashMap<i32, Vec<Equation>> = HashMap::new();
+
+    for (index, eq) in equations.iter().enumerate() {
+        let mut tmp_extracted_constants: HashMap<i32, Vec<Equation>> = HashMap::new();
+        let mut tmp_extracted_equations: HashMap<i32, Vec<Equation>> = HashMap::new();
+
+        let analysis = eq.analyse();
+        for var in &analysis {
+            let (sub_for, ex) = match is_in_extracted(&extracted_constants, &analysis, var) {
+                Ok(ok) => ok,
+                Err(_) => {
+                    match is_in_extracted(&extracted_equations, &analysis, var) {
+                        Ok(ok) => ok,
+                        Err(_) => (-0i32, Val(True))
+                    }
+                }
+            };
+
+            let sub = match ex {
+                Val(val) => vec![val],
+                Xor(vals) => vals
+            };
+
+            if let Ok(eq) = eq.evaluate(var, sub_for, sub) {
+                if eq.count_members() == 2 {
+                    tmp_extracted_constants.entry(index as i32).or_default().push(eq)
+                } else {
+                    tmp_extracted_equations.entry(index as i32).or_default().push(eq)
+                }
+            }
+        }
+
+        merge_maps(&mut extracted_constants, tmp_extracted_constants);
+        merge_maps(&mut extracted_equations, tmp_extracted_equations);
+    }
+
+
+    // extracted equations follow from the original ones, but an equation whose substitution
+    // fails is never extracted, so the search checks the original ones as well
+    let mut extracted_equations = merge_equations(&extracted_equations);
+    extracted_equations.extend(equations.iter().cloned());
+
+    solve(variables, merge_equations(&extracted_constants), extracted_equations, budget)
+}
+
+fn merge_maps(map1: &mut HashMap<i32, Vec<Equation>>, map2: HashMap<i32, Vec<Equation>>) {
+    for (key, equations) in map2 {
+        map1
+            .entry(key)
+            .and_modify(|existing_equations| existing_equations.extend(equations.clone()))
+            .or_insert(equations);
+    }
+}
+
+fn merge_equations(map: &HashMap<i32, Vec<Equation>>) -> Vec<Equation> {
+    let mut merged_equations: Vec<Equation> = Vec::new();
+
+    for equations in map.values() {
+        merged_equations.extend(equations.iter().cloned());
+    }
+
+    merged_equations.sort();
+    merged_equations.dedup();
+
+    merged_equations
+}
+
+/// Solution as a string of `0` and `1`, first switch first
+pub fn bool_vec_to_string(bool_vec: &[bool]) -> String {
+    let mut result = String::new();
+
+    for &b in bool_vec {
+        if b {
+            result.push('1');
+        } else {
+            result.push('0');
+        }
+    }
+
+    result
+}
+
+impl Expression {
+    /// The values XORed together, one for `Val`
+    pub fn values(&self) -> &[Value] {
+        match self {
+            Val(val) => std::slice::from_ref(val),
+            Xor(vals) => vals
+        }
+    }
+
+    /// Same as `Display`, but with switch names
+    pub fn named(&self, names: &Names) -> String {
+        let value = |val: &Value| match val {
+            True => "1".to_owned(),
+            False => "0".to_owned(),
+            Var(i) => names.switch(*i),
+        };
+
+        match self {
+            Val(val) => value(val),
+            Xor(vals) => vals.iter().map(value).collect::<Vec<String>>().join(" XOR ")
+        }
+    }
+}
+
+impl Display for Equation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.named(&Names::default()))
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.named(&Names::default()))
+    }
+}
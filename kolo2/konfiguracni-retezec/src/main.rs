@@ -1,655 +1,582 @@
-use std::collections::{HashMap, HashSet};
+use std::any::Any;
 use std::env;
-use std::fmt::{Display, Formatter};
-use std::fs::File;
-use std::io::Read;
-use crate::Expression::{Val, Xor};
-use crate::Value::{True, False, Var};
-
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
-struct Equation {
-    left: Expression,
-    right: Expression,
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+use std::time::{Duration, SystemTime};
+use konfiguracni_retezec::{batch, bool_vec_to_string, dense, Budget, System};
+use konfiguracni_retezec::dot::to_dot;
+use konfiguracni_retezec::explain::{explain, unsat_core};
+use konfiguracni_retezec::grid::Grid;
+use konfiguracni_retezec::project::project;
+use konfiguracni_retezec::proof::to_proof;
+use konfiguracni_retezec::check::check;
+use konfiguracni_retezec::witness::{witness, WitnessOrder};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Format {
+    Text,
+    Json,
+    Dot,
 }
 
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
-enum Expression {
-    Val(Value),
-    Xor(Vec<Value>),
-}
+/// Exit code when `--timeout` or `--max-nodes` stopped the search
+const BUDGET_EXCEEDED: i32 = 2;
 
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
-enum Value {
-    True,
-    False,
-    Var(i32),
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Command {
+    Solve,
+    Grid,
+    Explain,
+    Batch,
+    Check,
 }
 
-impl Equation {
-    fn count_members(&self) -> usize {
-        let mut counter = 0;
-
-        match &self.left {
-            Val(_) => counter += 1,
-            Xor(vals) => counter += vals.len()
-        }
-
-        match &self.right {
-            Val(_) => counter += 1,
-            Xor(vals) => counter += vals.len()
-        }
-
-        counter
-    }
+#[derive(Debug, Clone)]
+struct Options {
+    command: Command,
+    file_name: String,
+    format: Format,
+    timeout: Option<Duration>,
+    max_nodes: Option<u64>,
+    dense: bool,
+    threads: usize,
+    /// switch to explain, number or name
+    var: Option<String>,
+    /// `--assume` as given, switches are resolved once the system is read
+    assumptions: Option<String>,
+    /// `--project` as given, resolved like the assumptions
+    projection: Option<String>,
+    /// list the projected assignments, not just count them
+    enumerate: bool,
+    /// where to write the proof if there is no solution, the proof to check for `check`
+    proof: Option<String>,
+    /// which solution to print, the search's first one if not given
+    witness: Option<WitnessOrder>,
+}
 
-    fn has_var(&self, var: &i32) -> Result<bool, ()> {
-        match &self.left {
-            Val(val) => {
-                if let Var(v) = val {
-                    if var == v { return Ok(true); }
-                }
-            }
-            Xor(vals) => {
-                for val in vals {
-                    if let Var(v) = val {
-                        if var == v { return Ok(true); }
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut command = Command::Solve;
+        let mut file_name: Option<String> = None;
+        let mut format = Format::Text;
+        let mut timeout: Option<Duration> = None;
+        let mut max_nodes: Option<u64> = None;
+        let mut dense = false;
+        let mut threads = dense::default_threads();
+        let mut var: Option<String> = None;
+        let mut assumptions: Option<String> = None;
+        let mut projection: Option<String> = None;
+        let mut enumerate = false;
+        let mut proof: Option<String> = None;
+        let mut witness_order: Option<String> = None;
+        let mut seed: Option<u64> = None;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "grid" if file_name.is_none() && command == Command::Solve => command = Command::Grid,
+                "explain" if file_name.is_none() && command == Command::Solve => command = Command::Explain,
+                "batch" if file_name.is_none() && command == Command::Solve => command = Command::Batch,
+                "check" if file_name.is_none() && command == Command::Solve => command = Command::Check,
+                "--proof" => {
+                    match args.next() {
+                        Some(path) => proof = Some(path.clone()),
+                        None => return Err("--proof has to be followed by a file name".to_owned()),
                     }
                 }
-            }
-        }
-
-        match &self.right {
-            Val(val) => {
-                if let Var(v) = val {
-                    if var == v { return Ok(false); }
-                }
-            }
-            Xor(vals) => {
-                for val in vals {
-                    if let Var(v) = val {
-                        if var == v { return Ok(false); }
+                "--var" => {
+                    match args.next() {
+                        Some(switch) => var = Some(switch.clone()),
+                        None => return Err("--var has to be followed by a switch".to_owned()),
                     }
                 }
-            }
-        }
-
-        Err(())
-    }
-
-    fn evaluate(&self, var: &i32, substitution_for: i32, substitution: Vec<Value>) -> Result<Self, ()> {
-        let side = match self.has_var(var) {
-            Ok(side) => side,
-            Err(_) => return Err(())
-        };
-
-        let mut vals: Vec<Value> = Vec::new();
-        match &self.left {
-            Val(val) => { if !side { vals.push(val.clone()) } }
-            Xor(vls) => {
-                for val in vls {
-                    if val != &Var(*var) { vals.push(val.clone()) }
-                }
-            }
-        }
-        match &self.right {
-            Val(val) => { if side { vals.push(val.clone()) } }
-            Xor(v) => {
-                for val in v {
-                    if val != &Var(*var) { vals.push(val.clone()) }
-                }
-            }
-        }
-
-        // substitute
-        let vals = if substitution_for >= 0 {
-            let mut sub = 0;
-            let mut new_vals: Vec<Value> = Vec::new();
-            for val in &vals {
-                if val == &Var(substitution_for) {
-                    sub += 1;
-                } else {
-                    new_vals.push(val.clone())
-                }
-            }
-            let mut vals = new_vals;
-            for _ in 0..sub {
-                vals.extend(substitution.clone())
-            }
-            vals
-        } else { vals };
-
-        let mut eq = Equation {
-            left: Val(Var(*var)),
-            right: if vals.len() == 1 {
-                Val(vals[0].clone())
-            } else {
-                Xor(vals)
-            },
-        };
-        match eq.solve_for_left() {
-            Ok(_) => Ok(eq),
-            Err(_) => Err(())
-        }
-    }
-
-    /// Returns Err if equation is unsolvable (same variable on both sides)
-    fn solve_for_left(&mut self) -> Result<(), ()> {
-        let looking_for_val = if let Val(val) = &self.left {
-            val
-        } else { panic!("Left side of equation has to be Val") };
-
-        let simplified_equation = match &self.right {
-            Val(val) => {
-                if val == looking_for_val {
-                    return Err(());
-                }
-                Val(val.clone())
-            }
-            Xor(expression) => {
-                let mut found_numbers: Vec<bool> = Vec::new();
-                let mut found_vars: HashSet<i32> = HashSet::new();
-
-                for val in expression {
-                    if val == looking_for_val { return Err(()); }
-                    match val {
-                        True => found_numbers.push(true),
-                        False => found_numbers.push(false),
-                        Var(var) => {
-                            if found_vars.contains(var) {
-                                found_vars.remove(var);
-                                found_numbers.push(false);
-                            } else { found_vars.insert(*var); }
-                        }
+                "--format" => {
+                    format = match args.next().map(|s| s.as_str()) {
+                        Some("text") => Format::Text,
+                        Some("json") => Format::Json,
+                        Some("dot") => Format::Dot,
+                        _ => return Err("--format has to be followed by \"text\", \"json\" or \"dot\"".to_owned()),
                     }
                 }
-
-                let mut result = found_numbers.pop().expect("Expect at least one val in expression");
-                if !found_numbers.is_empty() {
-                    for num in found_numbers {
-                        result ^= num
+                "--timeout" => {
+                    match args.next().and_then(|s| s.parse::<f64>().ok()) {
+                        Some(seconds) if seconds >= 0.0 => timeout = Some(Duration::from_secs_f64(seconds)),
+                        _ => return Err("--timeout has to be followed by number of seconds".to_owned()),
                     }
                 }
-
-                let mut vals: Vec<Value> = Vec::new();
-
-                vals.push(match result {
-                    true => True,
-                    false => False
-                });
-
-                for var in found_vars {
-                    vals.push(Var(var))
-                }
-
-                if vals.len() == 1 {
-                    Val(vals[0].clone())
-                } else {
-                    Xor(vals)
+                "--max-nodes" => {
+                    match args.next().and_then(|s| s.parse::<u64>().ok()) {
+                        Some(nodes) => max_nodes = Some(nodes),
+                        None => return Err("--max-nodes has to be followed by number of nodes".to_owned()),
+                    }
                 }
-            }
-        };
-        self.right = simplified_equation;
-
-        Ok(())
-    }
-
-    fn analyse(&self) -> Vec<i32> {
-        let mut vars: Vec<i32> = Vec::new();
-        match &self.left {
-            Val(val) => {
-                if let Var(var) = val { vars.push(*var) }
-            }
-            Xor(vals) => {
-                for val in vals {
-                    if let Var(var) = val { vars.push(*var) }
+                "--assume" => {
+                    match args.next() {
+                        Some(list) => assumptions = Some(list.clone()),
+                        None => return Err("--assume has to be followed by <switch>=0|1,...".to_owned()),
+                    }
                 }
-            }
-        }
-        vars
-    }
-
-    fn check_equation_validity(&self, variables: &HashMap<i32, bool>) -> bool {
-        let mut vars: Vec<i32> = Vec::new();
-        let mut must_equal_to: Option<bool> = None;
-
-        // collect variables and "must_equal_to"
-        match &self.left {
-            Val(val) => {
-                match val {
-                    True => must_equal_to = Some(true),
-                    False => must_equal_to = Some(false),
-                    Var(var) => vars.push(*var)
+                "--project" => {
+                    match args.next() {
+                        Some(list) => projection = Some(list.clone()),
+                        None => return Err("--project has to be followed by switches like 1,2,5-9".to_owned()),
+                    }
                 }
-            }
-            Xor(expression) => {
-                for val in expression {
-                    match val {
-                        True => must_equal_to = Some(true),
-                        False => must_equal_to = Some(false),
-                        Var(var) => vars.push(*var)
+                "--enumerate" => enumerate = true,
+                "--witness" => {
+                    match args.next() {
+                        Some(order) => witness_order = Some(order.clone()),
+                        None => return Err("--witness has to be followed by lexmin, lexmax, minweight or random".to_owned()),
                     }
                 }
-            }
-        }
-
-        match &self.right {
-            Val(val) => {
-                match val {
-                    True => must_equal_to = Some(true),
-                    False => must_equal_to = Some(false),
-                    Var(var) => vars.push(*var)
+                "--seed" => {
+                    match args.next().and_then(|s| s.parse::<u64>().ok()) {
+                        Some(number) => seed = Some(number),
+                        None => return Err("--seed has to be followed by a number".to_owned()),
+                    }
                 }
-            }
-            Xor(expression) => {
-                for val in expression {
-                    match val {
-                        True => must_equal_to = Some(true),
-                        False => must_equal_to = Some(false),
-                        Var(var) => vars.push(*var)
+                "--dense" => dense = true,
+                "--threads" => {
+                    match args.next().and_then(|s| s.parse::<usize>().ok()) {
+                        Some(count) if count > 0 => threads = count,
+                        _ => return Err("--threads has to be followed by positive number of threads".to_owned()),
                     }
                 }
+                _ if file_name.is_none() && !arg.starts_with("--") => file_name = Some(arg.clone()),
+                _ if command == Command::Check && proof.is_none() && !arg.starts_with("--") => proof = Some(arg.clone()),
+                _ => return Err(format!("Neznamy argument \"{}\".", arg)),
             }
         }
 
-        if must_equal_to.is_none() {
-            panic!("Something is very wrong")
+        if command == Command::Explain && var.is_none() {
+            return Err("explain needs --var <switch>".to_owned());
         }
-
-        // substitute variables
-        let mut substituted_vars: Vec<bool> = Vec::new();
-        for var in vars {
-            match variables.get(&var) {
-                None => return true,
-                Some(val) => substituted_vars.push(*val)
-            }
+        if command == Command::Batch && (var.is_some() || projection.is_some()) {
+            return Err("batch can't be combined with --var or --project".to_owned());
         }
-
-        let mut result = substituted_vars.pop().expect("Expect at least one val in expression");
-        if !substituted_vars.is_empty() {
-            for num in substituted_vars {
-                result ^= num
-            }
+        if command == Command::Batch && (format == Format::Dot || proof.is_some()) {
+            return Err("batch can't be combined with --format dot or --proof".to_owned());
+        }
+        if command == Command::Check && file_name.is_some() && proof.is_none() {
+            return Err("check needs the input file and the proof".to_owned());
+        }
+        if proof.is_some() && assumptions.is_some() && command != Command::Check {
+            return Err("--proof can't be combined with --assume, the proof has to hold for the input alone".to_owned());
+        }
+        if seed.is_some() && witness_order.as_deref() != Some("random") {
+            return Err("--seed only works with --witness random".to_owned());
+        }
+        let seed = seed.unwrap_or_else(|| {
+            SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64)
+        });
+        let witness = match witness_order.map(|order| WitnessOrder::parse(&order, seed)) {
+            Some(Some(order)) => Some(order),
+            Some(None) => return Err("--witness has to be followed by lexmin, lexmax, minweight or random".to_owned()),
+            None => None,
+        };
+        if enumerate && projection.is_none() {
+            return Err("--enumerate needs --project <switches>".to_owned());
         }
 
-        if Some(result) == must_equal_to {
-            return true;
+        match file_name {
+            Some(file_name) => Ok(Self {
+                command, file_name, format, timeout, max_nodes, dense, threads, var, assumptions, projection, enumerate,
+                proof, witness,
+            }),
+            None => Err("Zadejte cestu k souboru pro zadani.".to_owned()),
         }
-        false
     }
-}
 
-fn is_in_extracted(extracted_equations: &HashMap<i32, Vec<Equation>>, analysis: &[i32], except: &i32) -> Result<(i32, Expression), ()> {
-    for eqs in extracted_equations.values() {
-        for eq in eqs {
-            if let Val(Var(i)) = eq.left {
-                if &i == except {
-                    continue
-                } else if analysis.contains(&i) {
-                    return Ok((i, eq.right.clone()))
-                }
-            } else { panic!("This shouldn't happen") }
-        }
+    fn budget(&self) -> Budget {
+        Budget::new(self.timeout, self.max_nodes)
     }
-    Err(())
 }
 
-fn solve(variables: Vec<i32>, extracted_constants: Vec<Equation>, mut extracted_equations: Vec<Equation>) -> Result<(i32, String), ()> {
-    // DEBUG
-    // println!();
-    // println!("{:?}", variables);
-    // println!();
-    // println!("extracted_constants:");
-    // for eq in &extracted_constants {
-    //     println!("{}", eq);
-    // }
-    // println!();
-    // println!("extracted_equations:");
-    // for eq in &extracted_equations {
-    //     println!("{}", eq);
-    // }
-
-    let mut vars: HashMap<i32, bool> = HashMap::new();
-    let mut solutions: Vec<Vec<bool>> = Vec::new();
-
-    for const_ in &extracted_constants {
-        if let Val(Var(left)) = const_.left {
-            match &const_.right {
-                Val(val) => {
-                    match val {
-                        True => {
-                            if let Some(v) = vars.get(&left) {
-                                if v == &false {
-                                    return Err(());
-                                }
-                            } else {
-                                vars.insert(left, true);
-                            }
-                        },
-                        False => {
-                            if let Some(v) = vars.get(&left) {
-                                if v == &true {
-                                    return Err(());
-                                }
-                            } else {
-                                vars.insert(left, false);
-                            }
-                        },
-                        Var(_) => panic!()
-                    };
-                }
-                Xor(_) => panic!()
-            }
-        } else { panic!("This shouldn't happen") }
-    }
+fn main() {
+    let args: Vec<String> = env::args().collect();
 
-    extracted_equations.sort_by_key(|eq| {
-        if let Xor(values) = &eq.right {
-            values.len()
-        } else {
-            0
+    let options = match Options::parse(&args[1..]) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
         }
-    });
-
-    // DEBUG
-    // println!();
-    // println!("{:?}", variables);
-    // println!();
-    // println!("extracted_constants:");
-    // for eq in &extracted_constants {
-    //     println!("{}", eq);
-    // }
-    // println!();
-    // println!("extracted_equations:");
-    // for eq in &extracted_equations {
-    //     println!("{}", eq);
-    // }
+    };
 
-    solve_recursion(vars, &variables, &mut solutions, &extracted_equations);
+    if options.command == Command::Batch {
+        run_batch(&options);
+        return;
+    }
 
-    let solutions_length = solutions.len() as i32;
-    if solutions_length == 0 {
-        return Err(());
+    if options.command == Command::Check {
+        run_check(&options);
+        return;
     }
 
-    Ok((solutions_length, bool_vec_to_string(&solutions[0])))
-}
+    let (mut system, grid) = match load(&options) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
 
-fn solve_recursion (
-    variables: HashMap<i32, bool>,
-    all_vars: &Vec<i32>,
-    solutions: &mut Vec<Vec<bool>>,
-    extracted_equations: &Vec<Equation>
-) {
-    // DEBUG
-    // println!();
-    // println!();
-    // println!("{:?}", all_vars);
-    // println!("{}", variables.len());
-    // println!("{:?}", variables);
-    // println!("{:?}", solutions.len());
-    // println!("extracted_equations:");
-    // for eq in extracted_equations {
-    //     println!("{}", eq);
-    // }
-
-    let mut valid = true;
-    for eq in extracted_equations {
-        if !eq.check_equation_validity(&variables) {
-            valid = false;
+    if let Some(assumptions) = &options.assumptions {
+        if let Err(e) = system.assume_all(assumptions) {
+            println!("Err: {}", e);
+            return;
         }
     }
 
-    if valid && variables.len() == all_vars.len() {
-        let mut solution: Vec<bool> = Vec::new();
-        for v in all_vars {
-            solution.push(*variables.get(v).unwrap())
+    if let Some(path) = &options.proof {
+        if let Some(proof) = to_proof(&system) {
+            if let Err(e) = fs::write(path, proof + "\n") {
+                println!("Err: {}", e);
+                return;
+            }
         }
-        solutions.push(solution);
-        return;
     }
-    if variables.len() == all_vars.len() || !valid {
+
+    let render = |bits: &str| match &grid {
+        Some(grid) => grid.render(bits),
+        None => render_witness(&system, bits),
+    };
+
+    if let Some(var) = &options.var {
+        print_explanation(&system, var);
         return;
     }
 
-    // kvuli tomuhle to asi dlouho trva :((((
-    let mut current_var = -1;
-    for var in all_vars {
-        if variables.contains_key(var) {
-            continue
-        }
-        current_var = *var;
-        break;
+    if options.format == Format::Dot {
+        println!("{}", to_dot(&system));
+        return;
     }
 
-    if current_var == -1 {
-        panic!("This shouldn't happen")
+    if let Some(projection) = &options.projection {
+        print_projection(&system, projection, options.format, options.enumerate);
+        return;
     }
 
-    let mut f = variables.clone();
-    let mut t = variables.clone();
-    f.insert(current_var, false);
-    t.insert(current_var, true);
-    solve_recursion(f, all_vars, solutions, extracted_equations); // try new var with 0
-    solve_recursion(t, all_vars, solutions, extracted_equations); // try new var with 1
-}
+    if options.format == Format::Json {
+        match report(&system, options.witness) {
+            Ok(report) => println!("{}", report),
+            Err(e) => println!("Err: {}", e),
+        }
+        return;
+    }
 
+    // contradicting assumptions are reported with the scripts responsible
+    if system.assumptions > 0 {
+        if let Some(core) = unsat_core(&system) {
+            println!("0");
+            for index in core {
+                println!("{}", system.describe(index));
+            }
+            return;
+        }
+    }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+    if let Some(order) = options.witness {
+        match witness(&system, order) {
+            Ok(Some(solution)) => println!("{}\n{}", system.count(), render(&bool_vec_to_string(&solution))),
+            Ok(None) => println!("0"),
+            Err(e) => println!("Err: {}", e),
+        }
+        return;
+    }
 
-    if args.len() != 2 {
-        println!("Zadejte cestu k souboru pro zadani.");
-        println!("{:?} {}", args, args.len());
+    if options.dense {
+        print_dense_result(&system, options.threads, render);
         return;
     }
 
-    let file_name = &args[1];
+    let mut budget = options.budget();
+    let result = system.search(&mut budget);
+    print_search_result(result, &budget, render);
+}
 
-    let mut file = match File::open(file_name) {
-        Ok(file) => {
-            file
-        }
+/// Solves every instance of a batch file on `--threads` threads and prints their results in input order,
+/// errors as `Err: instance I: ...` in place of the result. Exits with 1 if any instance failed, otherwise
+/// with `BUDGET_EXCEEDED` if the budget (per instance) ran out for any of them.
+fn run_batch(options: &Options) {
+    let contents = match read(&options.file_name) {
+        Ok(contents) => contents,
         Err(e) => {
-            println!("Err: {}", e);
-            return;
+            println!("{}", e);
+            process::exit(1);
         }
     };
 
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).expect("cannot read file");
-
-    let mut c: Vec<&str> = contents.split('\n').collect();
-
-    let first_line = c.remove(0);
-
-    let f_l: Vec<&str> = first_line.split_whitespace().collect();
-
-    let vars: i32 = match f_l[0].parse() {
-        Ok(num) => num,
-        Err(_) => {
-            println!("failed to parse first line");
-            return;
+    let instances = match batch::split(&contents) {
+        Ok(instances) => instances,
+        Err(e) => {
+            println!("Err: {}", e);
+            process::exit(1);
         }
     };
 
-    let variables = (1..=vars).collect::<Vec<i32>>();
+    // a panic only fails its own instance, the message is reported like any other error
+    let results = batch::map_parallel(&instances, options.threads, |instance| match instance {
+        Ok(system) => panic::catch_unwind(AssertUnwindSafe(|| solve_instance(system.clone(), options)))
+            .unwrap_or_else(|payload| Err(panic_message(payload.as_ref()))),
+        Err(e) => Err(e.clone()),
+    });
 
-    let scripts: i32 = match f_l[1].parse() {
-        Ok(num) => num,
-        Err(_) => {
-            println!("failed to parse first line");
-            return;
+    let mut exceeded = false;
+    let mut failed = false;
+    for (index, result) in results.into_iter().enumerate() {
+        match result {
+            Ok((output, budget_exceeded)) => {
+                println!("{}", output);
+                exceeded |= budget_exceeded;
+            }
+            Err(e) => {
+                println!("Err: instance {}: {}", index + 1, e);
+                failed = true;
+            }
         }
-    };
+    }
 
-    let mut lefts: Vec<Vec<Value>> = Vec::new();
+    if failed {
+        process::exit(1);
+    }
+    if exceeded {
+        process::exit(BUDGET_EXCEEDED);
+    }
+}
 
-    for a in 1..scripts+1 {
-        let mut left: Vec<Value> = Vec::new();
+/// Text of a caught panic, `panic!` payloads are `&str` or `String`
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => format!("panicked: {}", message),
+        None => match payload.downcast_ref::<String>() {
+            Some(message) => format!("panicked: {}", message),
+            None => "panicked".to_owned(),
+        },
+    }
+}
 
-        for (i, line) in c.iter().enumerate() {
-            let numbers: Vec<i32> = line
-                .split_whitespace()
-                .skip(1)
-                .filter_map(|x| x.parse().ok())
-                .collect();
+/// Checks the proof against the input without the solver, exits with 1 if it doesn't hold
+fn run_check(options: &Options) {
+    let proof = options.proof.as_ref().unwrap();
+    let result = read(&options.file_name).and_then(|input| Ok((input, read(proof)?)));
 
-            if numbers.contains(&a) {
-                left.push(Var(i as i32 + 1));
-            }
+    match result.and_then(|(input, proof)| check(&input, &proof)) {
+        Ok(steps) => println!("ok, {} steps", steps),
+        Err(e) => {
+            println!("Err: {}", e);
+            process::exit(1);
         }
+    }
+}
 
-        lefts.push(left);
+/// Output of one batch instance, the same as for a single system, and whether the budget ran out
+fn solve_instance(mut system: System, options: &Options) -> Result<(String, bool), String> {
+    if let Some(assumptions) = &options.assumptions {
+        system.assume_all(assumptions)?;
     }
 
-    lefts.sort_by_key(|inner_vec| inner_vec.len());
+    if options.format == Format::Json {
+        return Ok((report(&system, options.witness)?, false));
+    }
 
-    // println!("{:#?}", lefts);
+    if system.assumptions > 0 {
+        if let Some(core) = unsat_core(&system) {
+            let lines: Vec<String> = core.iter().map(|index| system.describe(*index)).collect();
+            return Ok((format!("0\n{}", lines.join("\n")), false));
+        }
+    }
 
-    let mut equations: Vec<Equation> = Vec::new();
+    if let Some(order) = options.witness {
+        return Ok(match witness(&system, order)? {
+            Some(solution) => (format!("{}\n{}", system.count(), render_witness(&system, &bool_vec_to_string(&solution))), false),
+            None => ("0".to_owned(), false),
+        });
+    }
 
-    for left in lefts {
-        equations.push(Equation {
-            left: Xor(left),
-            right: Val(True),
+    if options.dense {
+        let elimination = system.dense(1);
+        return Ok(match elimination.witness() {
+            Some(witness) => (format!("{}\n{}", elimination.count(), render_witness(&system, &bool_vec_to_string(&witness))), false),
+            None => ("0".to_owned(), false),
         });
     }
 
-    let mut extracted_constants: HashMap<i32, Vec<Equation>> = HashMap::new();
-    let mut extracted_equations: HashMap<i32, Vec<Equation>> = HashMap::new();
+    let mut budget = options.budget();
+    let result = system.search(&mut budget);
+    let output = match result {
+        Some((solutions, string)) => format!("{}\n{}", solutions, render_witness(&system, &string)),
+        None => "0".to_owned(),
+    };
 
-    if equations.is_empty() {
-        println!("0");
-        return;
+    if budget.exceeded() {
+        return Ok((format!("budget exceeded after {} nodes\n>={}", budget.nodes(), output), true));
     }
+    Ok((output, false))
+}
 
-    for (index, eq) in equations.iter().enumerate() {
-        // DEBUG
-        // println!("{}", eq);
+/// Whole input file, stdin for `-`
+fn read(file_name: &str) -> Result<String, String> {
+    let mut contents = String::new();
+    open(file_name)?.read_to_string(&mut contents).map_err(|e| format!("Err: {}", e))?;
+    Ok(contents)
+}
 
-        let mut tmp_extracted_constants: HashMap<i32, Vec<Equation>> = HashMap::new();
-        let mut tmp_extracted_equations: HashMap<i32, Vec<Equation>> = HashMap::new();
+/// Input file or stdin for `-`, reporting progress of big inputs on stderr
+fn open(file_name: &str) -> Result<Progress<Box<dyn Read>>, String> {
+    if file_name == "-" {
+        return Ok(Progress::new(Box::new(io::stdin().lock()), None));
+    }
 
-        let analysis = eq.analyse();
-        for var in &analysis {
-            let (sub_for, ex) = match is_in_extracted(&extracted_constants, &analysis, var) {
-                Ok(ok) => ok,
-                Err(_) => {
-                    match is_in_extracted(&extracted_equations, &analysis, var) {
-                        Ok(ok) => ok,
-                        Err(_) => (-0i32, Val(True))
-                    }
-                }
-            };
-
-            let sub = match ex {
-                Val(val) => vec![val],
-                Xor(vals) => vals
-            };
-
-            match eq.evaluate(var, sub_for, sub.clone()) {
-                Ok(eq) => {
-                    // DEBUG
-                    // println!("eq members: {} eq: {}", eq.count_members(), eq);
-
-                    if eq.count_members() == 2 {
-                        tmp_extracted_constants.entry(index as i32).or_default().push(eq)
-                    } else {
-                        tmp_extracted_equations.entry(index as i32).or_default().push(eq)
-                    }
-                }
-                Err(_) => {
-                    // DEBUG
-                    // println!("cannot evaluate {} with substitution for {} and with substitution {:?}", &var, sub_for, sub)
-                }
-            }
+    match File::open(file_name) {
+        Ok(file) => {
+            let total = file.metadata().ok().map(|metadata| metadata.len());
+            Ok(Progress::new(Box::new(file), total))
         }
-
-        merge_maps(&mut extracted_constants, tmp_extracted_constants);
-        merge_maps(&mut extracted_equations, tmp_extracted_equations);
+        Err(e) => Err(format!("Err: {}", e))
     }
+}
+
+/// Bytes between two progress reports
+const PROGRESS_STEP: u64 = 256 << 20;
 
-    // DEBUG
-    // println!("\n");
-    // println!("extracted_constants:");
-    // for (index, eqs) in &extracted_constants {
-    //     println!("{}. script:", index);
-    //     for eq in eqs { println!("{}", eq); }
-    // }
-    // println!();
-    // println!("extracted_equations:");
-    // for (index, eqs) in &extracted_equations {
-    //     println!("{}. script:", index);
-    //     for eq in eqs { println!("{}", eq); }
-    // }
+/// Reader printing `read X MiB of Y MiB` on stderr after every `PROGRESS_STEP` bytes
+struct Progress<R: Read> {
+    inner: R,
+    read: u64,
+    total: Option<u64>,
+}
 
-    match solve(variables, merge_equations(&extracted_constants), merge_equations(&extracted_equations)) {
-        Ok((solutions, string)) => println!("{}\n{}", solutions, string),
-        Err(_) => println!("0")
+impl<R: Read> Progress<R> {
+    fn new(inner: R, total: Option<u64>) -> Self {
+        Self { inner, read: 0, total }
     }
 }
 
-fn merge_maps(map1: &mut HashMap<i32, Vec<Equation>>, map2: HashMap<i32, Vec<Equation>>) {
-    for (key, equations) in map2 {
-        map1
-            .entry(key)
-            .and_modify(|existing_equations| existing_equations.extend(equations.clone()))
-            .or_insert(equations);
+impl<R: Read> Read for Progress<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        let before = self.read;
+        self.read += read as u64;
+
+        if self.read / PROGRESS_STEP > before / PROGRESS_STEP {
+            match self.total {
+                Some(total) => eprintln!("read {} MiB of {} MiB", self.read >> 20, total >> 20),
+                None => eprintln!("read {} MiB", self.read >> 20),
+            }
+        }
+        Ok(read)
     }
 }
 
-fn merge_equations(map: &HashMap<i32, Vec<Equation>>) -> Vec<Equation> {
-    let mut merged_equations: Vec<Equation> = Vec::new();
+/// JSON report, with the witness chosen by `order` if given
+fn report(system: &System, order: Option<WitnessOrder>) -> Result<String, String> {
+    let mut report = system.report();
+    if let Some(order) = order {
+        report.witness = witness(system, order)?;
+    }
+    Ok(report.to_json())
+}
 
-    for equations in map.values() {
-        merged_equations.extend(equations.iter().cloned());
+/// Reads the system from the input file, the grid too if it is a grid panel
+fn load(options: &Options) -> Result<(System, Option<Grid>), String> {
+    if options.command == Command::Grid {
+        let grid = Grid::parse(&read(&options.file_name)?).map_err(|e| format!("Err: {}", e))?;
+        return Ok((grid.system(), Some(grid)));
     }
 
-    merged_equations.sort();
-    merged_equations.dedup();
+    // inputs can be gigabytes, so they are parsed while reading
+    let input = BufReader::with_capacity(1 << 20, open(&options.file_name)?);
+    Ok((System::read(input)?, None))
+}
+
+/// Prints the scripts forcing `var`, given by number or name
+fn print_explanation(system: &System, var: &str) {
+    let result = system.switch(var).and_then(|switch| explain(system, switch));
 
-    merged_equations
+    match result {
+        Ok(explanation) => println!("{}", explanation.to_text(system)),
+        Err(e) => println!("{}", e)
+    }
 }
 
-fn bool_vec_to_string(bool_vec: &Vec<bool>) -> String {
-    let mut result = String::new();
+/// Prints the number of distinct assignments of the given switches over all the solutions,
+/// and the assignments themselves with `enumerate`. Unsatisfiable systems get the usual report.
+fn print_projection(system: &System, list: &str, format: Format, enumerate: bool) {
+    let switches = match system.switch_list(list) {
+        Ok(switches) => switches,
+        Err(e) => {
+            println!("Err: {}", e);
+            return;
+        }
+    };
 
-    for &b in bool_vec {
-        if b {
-            result.push('1');
-        } else {
-            result.push('0');
+    let projection = match project(system, &switches) {
+        Some(projection) => projection,
+        None if format == Format::Json => return println!("{}", system.report().to_json()),
+        None => return println!("0"),
+    };
+
+    if format == Format::Json {
+        println!("{}", projection.to_json(&system.names, enumerate));
+        return;
+    }
+
+    println!("{}", projection.count());
+    if enumerate {
+        for assignment in projection.assignments() {
+            if system.names.switches.is_empty() {
+                println!("{}", bool_vec_to_string(&assignment));
+            } else {
+                let pairs: Vec<String> = switches
+                    .iter()
+                    .zip(&assignment)
+                    .map(|(switch, value)| format!("{}={}", system.names.switch(*switch), *value as u8))
+                    .collect();
+                println!("{}", pairs.join(" "));
+            }
         }
     }
+}
+
+/// Witness as bits, or as `name=value` pairs when the input names its switches
+fn render_witness(system: &System, bits: &str) -> String {
+    if system.names.switches.is_empty() {
+        return bits.to_owned();
+    }
 
-    result
+    let solution: Vec<bool> = bits.chars().map(|ch| ch == '1').collect();
+    system.names.assignment(&solution)
 }
 
-impl Display for Equation {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} = {}", self.left, self.right)
+/// Prints the exact number of solutions and the one with every free switch off
+fn print_dense_result(system: &System, threads: usize, render: impl Fn(&str) -> String) {
+    let elimination = system.dense(threads);
+
+    match elimination.witness() {
+        Some(witness) => println!("{}\n{}", elimination.count(), render(&bool_vec_to_string(&witness))),
+        None => println!("0")
     }
 }
 
-impl Display for Expression {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Val(val) => write!(f, "{}", match val {
-                True => "1".to_owned(),
-                False => "0".to_owned(),
-                Var(i) => format!("i_{}", i),
-            }),
-            Xor(vals) => {
-                let mut string = String::new();
-                for val in vals {
-                    string.push_str(&match val {
-                        True => "1 XOR ".to_owned(),
-                        False => "0 XOR ".to_owned(),
-                        Var(i) => format!("i_{} XOR ", i),
-                    })
-                }
-                write!(f, "{}", string.trim_end_matches(" XOR "))
-            }
+/// Prints the number of solutions and the first one. If the budget ran out, prints
+/// the number of explored nodes and what was found so far as a lower bound instead
+/// and exits with `BUDGET_EXCEEDED`.
+fn print_search_result(result: Option<(i32, String)>, budget: &Budget, render: impl Fn(&str) -> String) {
+    if budget.exceeded() {
+        println!("budget exceeded after {} nodes", budget.nodes());
+        match result {
+            Some((solutions, string)) => println!(">={}\n{}", solutions, render(&string)),
+            None => println!(">=0")
         }
+        process::exit(BUDGET_EXCEEDED);
+    }
+
+    match result {
+        Some((solutions, string)) => println!("{}\n{}", solutions, render(&string)),
+        None => println!("0")
     }
-}
\ No newline at end of file
+}
+
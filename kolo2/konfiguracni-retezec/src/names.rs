@@ -0,0 +1,96 @@
+use std::collections::{BTreeMap, HashSet};
+
+/// Optional names of switches and scripts, anything unnamed falls back to its number
+/// (`i_N` for switches like in equations, plain `N` for scripts).
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Names {
+    pub switches: BTreeMap<i32, String>,
+    pub scripts: BTreeMap<usize, String>,
+}
+
+impl Names {
+    pub fn is_empty(&self) -> bool {
+        self.switches.is_empty() && self.scripts.is_empty()
+    }
+
+    /// Name of switch `switch`, numbered from 1
+    pub fn switch(&self, switch: i32) -> String {
+        match self.switches.get(&switch) {
+            Some(name) => name.clone(),
+            None => format!("i_{}", switch),
+        }
+    }
+
+    /// Name of script `script`, numbered from 1
+    pub fn script(&self, script: usize) -> String {
+        match self.scripts.get(&script) {
+            Some(name) => name.clone(),
+            None => script.to_string(),
+        }
+    }
+
+    /// Solution as `name=value` pairs, first switch first
+    pub fn assignment(&self, solution: &[bool]) -> String {
+        solution
+            .iter()
+            .enumerate()
+            .map(|(index, value)| format!("{}={}", self.switch(index as i32 + 1), *value as u8))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Parses the symbol table section, lines `switch <index> <name>` or `script <index> <name>`
+    pub fn parse(lines: &[&str], switches: usize, scripts: usize) -> Result<Self, String> {
+        let mut names = Self::default();
+        let mut used: HashSet<String> = HashSet::new();
+
+        for line in lines {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.is_empty() {
+                continue;
+            }
+            if parts.len() != 3 {
+                return Err(format!("name line \"{}\" has to be \"switch|script <index> <name>\"", line.trim()));
+            }
+
+            let index: usize = match parts[1].parse() {
+                Ok(index) => index,
+                Err(_) => return Err(format!("failed to parse index in name line \"{}\"", line.trim())),
+            };
+            let name = parts[2];
+            if !is_valid_name(name) {
+                return Err(format!("\"{}\" can't be used as a name", name));
+            }
+            if !used.insert(name.to_owned()) {
+                return Err(format!("name \"{}\" is used twice", name));
+            }
+
+            match parts[0] {
+                "switch" if (1..=switches).contains(&index) => {
+                    if names.switches.insert(index as i32, name.to_owned()).is_some() {
+                        return Err(format!("switch {} is named twice", index));
+                    }
+                }
+                "script" if (1..=scripts).contains(&index) => {
+                    if names.scripts.insert(index, name.to_owned()).is_some() {
+                        return Err(format!("script {} is named twice", index));
+                    }
+                }
+                "switch" | "script" => return Err(format!("{} {} doesn't exist", parts[0], index)),
+                other => return Err(format!("unknown kind \"{}\" in name line", other)),
+            }
+        }
+
+        Ok(names)
+    }
+}
+
+/// Names can't look like the numeric fallbacks and can't break `name=value` or the equation syntax
+fn is_valid_name(name: &str) -> bool {
+    let numeric = |s: &str| !s.is_empty() && s.chars().all(|ch| ch.is_ascii_digit());
+
+    !numeric(name)
+        && !name.strip_prefix("i_").is_some_and(numeric)
+        && name != "XOR"
+        && name.chars().all(|ch| ch.is_alphanumeric() || "_-.:/".contains(ch))
+}
@@ -0,0 +1,25 @@
+use crate::System;
+
+/// Largest system `brute_force` is willing to enumerate
+pub const MAX_SWITCHES: usize = 20;
+
+/// Every solution of the system, found by trying all 2^switches assignments.
+/// Slow but simple enough to check the real solvers against.
+pub fn brute_force(system: &System) -> Vec<Vec<bool>> {
+    assert!(system.switches <= MAX_SWITCHES, "brute force is limited to {} switches", MAX_SWITCHES);
+
+    let mut solutions = Vec::new();
+
+    for bits in 0u32..1 << system.switches {
+        // first switch is the most significant bit, so solutions come in lexicographic order
+        let assignment: Vec<bool> = (0..system.switches)
+            .map(|switch| bits >> (system.switches - 1 - switch) & 1 == 1)
+            .collect();
+
+        if system.equations.iter().all(|equation| equation.holds(&assignment)) {
+            solutions.push(assignment);
+        }
+    }
+
+    solutions
+}
@@ -0,0 +1,112 @@
+use crate::elimination::power_of_two;
+use crate::names::Names;
+use crate::report::string;
+use crate::{bool_vec_to_string, System};
+
+/// Projection of the affine solution space onto some switches:
+/// `offset + any combination of basis` gives every distinct visible assignment exactly once
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Projection {
+    pub switches: Vec<i32>,
+    pub offset: Vec<bool>,
+    /// linearly independent directions, restricted to `switches`
+    pub basis: Vec<Vec<bool>>,
+}
+
+/// Projects the solutions onto `switches` (numbered from 1), `None` if there is no solution
+pub fn project(system: &System, switches: &[i32]) -> Option<Projection> {
+    let elimination = system.elimination();
+    let solution = elimination.witness()?;
+    let free = elimination.free_variables();
+
+    let restrict = |vector: &[bool]| -> Vec<bool> {
+        switches.iter().map(|switch| vector[*switch as usize - 1]).collect()
+    };
+
+    // every free variable gives one direction of the solution space: itself and the pivots depending on it
+    let mut basis: Vec<Vec<bool>> = Vec::new();
+    let mut pivots: Vec<usize> = Vec::new();
+    for var in free {
+        let mut direction = vec![false; system.switches];
+        direction[var as usize - 1] = true;
+        for (row, pivot) in elimination.rows.iter().zip(&elimination.pivots) {
+            if row.has(var as usize - 1) {
+                direction[*pivot] = true
+            }
+        }
+
+        // keep only directions independent after the projection
+        let mut direction = restrict(&direction);
+        for (existing, pivot) in basis.iter().zip(&pivots) {
+            if direction[*pivot] {
+                for (a, b) in direction.iter_mut().zip(existing) {
+                    *a ^= b
+                }
+            }
+        }
+        if let Some(pivot) = direction.iter().position(|bit| *bit) {
+            basis.push(direction);
+            pivots.push(pivot);
+        }
+    }
+
+    Some(Projection { switches: switches.to_vec(), offset: restrict(&solution), basis })
+}
+
+impl Projection {
+    pub fn dimension(&self) -> usize {
+        self.basis.len()
+    }
+
+    /// Number of distinct visible assignments in decimal
+    pub fn count(&self) -> String {
+        power_of_two(self.dimension())
+    }
+
+    /// Every distinct visible assignment, values in the order of `switches`
+    pub fn assignments(&self) -> impl Iterator<Item = Vec<bool>> + '_ {
+        // which directions are added, a binary counter as wide as the basis (first direction lowest)
+        let none = vec![false; self.dimension()];
+        let combinations = std::iter::successors(Some(none), |combination| {
+            let mut next = combination.clone();
+            let lowest_unset = next.iter().position(|bit| !bit)?;
+            next[..lowest_unset].fill(false);
+            next[lowest_unset] = true;
+            Some(next)
+        });
+
+        combinations.map(move |combination| {
+            let mut assignment = self.offset.clone();
+            for (direction, _) in self.basis.iter().zip(&combination).filter(|(_, added)| **added) {
+                for (a, b) in assignment.iter_mut().zip(direction) {
+                    *a ^= b
+                }
+            }
+            assignment
+        })
+    }
+
+    /// ```text
+    /// {"switches":[..],"switch_names":[..],"dimension":D,"count":"<decimal>","assignments":["<bits>",..]|null}
+    /// ```
+    /// `assignments` are listed only when `enumerate` is set, bits in the order of `switches`.
+    pub fn to_json(&self, names: &Names, enumerate: bool) -> String {
+        let switches: Vec<String> = self.switches.iter().map(|switch| switch.to_string()).collect();
+        let switch_names: Vec<String> = self.switches.iter().map(|switch| string(&names.switch(*switch))).collect();
+
+        format!(
+            "{{\"switches\":[{}],\"switch_names\":[{}],\"dimension\":{},\"count\":\"{}\",\"assignments\":{}}}",
+            switches.join(","),
+            switch_names.join(","),
+            self.dimension(),
+            self.count(),
+            if enumerate {
+                let assignments: Vec<String> =
+                    self.assignments().map(|assignment| format!("\"{}\"", bool_vec_to_string(&assignment))).collect();
+                format!("[{}]", assignments.join(","))
+            } else {
+                "null".to_owned()
+            },
+        )
+    }
+}
@@ -0,0 +1,36 @@
+use crate::elimination::Row;
+use crate::explain::unsat_core;
+use crate::Expression::{Val, Xor};
+use crate::Value::False;
+use crate::{Equation, System};
+
+/// Proof that the scripts have no solution, `None` if they have one. Assumptions are left out,
+/// the proof has to hold for the input file alone. One step per line, each adding a script
+/// to the previous step and stating the result, the last one being `0 = 1`:
+/// ```text
+/// steps 3
+/// 1: script 1 => i_1 XOR i_3 = 1
+/// 2: 1 + script 2 => i_1 XOR i_2 = 0
+/// 3: 2 + script 3 => 0 = 1
+/// ```
+/// Switches and scripts are always numbered, names aren't part of the proof.
+pub fn to_proof(system: &System) -> Option<String> {
+    let mut scripts = system.clone();
+    scripts.equations.truncate(system.scripts());
+    scripts.assumptions = 0;
+
+    let core = unsat_core(&scripts)?;
+
+    let mut lines = vec![format!("steps {}", core.len())];
+    let mut sum = Row::from_equation(&Equation { left: Xor(Vec::new()), right: Val(False) }, 0, system.switches, 1);
+    for (step, script) in core.iter().enumerate() {
+        sum.add(&Row::from_equation(&system.equations[*script], 0, system.switches, 1));
+
+        let vars: Vec<String> = sum.variables().iter().map(|var| format!("i_{}", var)).collect();
+        let left = if vars.is_empty() { "0".to_owned() } else { vars.join(" XOR ") };
+        let previous = if step == 0 { String::new() } else { format!("{} + ", step) };
+        lines.push(format!("{}: {}script {} => {} = {}", step + 1, previous, script + 1, left, sum.constant as u8));
+    }
+
+    Some(lines.join("\n"))
+}
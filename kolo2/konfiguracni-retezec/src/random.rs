@@ -0,0 +1,118 @@
+use crate::System;
+
+/// Small deterministic generator (SplitMix64), good enough for test instances
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform number from `0..bound`
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// `true` with given probability
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
+
+/// Parameters of a random instance
+#[derive(Debug, Clone)]
+pub struct Generator {
+    pub switches: usize,
+    pub scripts: usize,
+    /// probability that a switch toggles a script
+    pub density: f64,
+    /// make sure the instance has a solution
+    pub planted: bool,
+}
+
+/// Random instance in the shape of the `N M` input: scripts toggled by each switch
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub switches: usize,
+    /// `scripts[i]` are switches (numbered from 1) toggling script `i + 1`
+    pub scripts: Vec<Vec<i32>>,
+    /// solution the instance was built around, if planted
+    pub planted: Option<Vec<bool>>,
+}
+
+impl Generator {
+    pub fn generate(&self, rng: &mut Rng) -> Instance {
+        let mut scripts: Vec<Vec<i32>> = (0..self.scripts)
+            .map(|_| (1..=self.switches as i32).filter(|_| rng.chance(self.density)).collect())
+            .collect();
+
+        let planted = if self.planted && self.switches > 0 {
+            let mut solution: Vec<bool> = (0..self.switches).map(|_| rng.chance(0.5)).collect();
+            if !solution.contains(&true) {
+                solution[rng.below(self.switches as u64) as usize] = true;
+            }
+            let on: Vec<i32> = (1..=self.switches as i32).filter(|switch| solution[*switch as usize - 1]).collect();
+
+            // fix the parity of every script the solution doesn't run by toggling one switch that is on
+            for script in scripts.iter_mut() {
+                let parity = script.iter().filter(|switch| solution[**switch as usize - 1]).count() % 2;
+                if parity == 0 {
+                    let switch = on[rng.below(on.len() as u64) as usize];
+                    match script.iter().position(|s| *s == switch) {
+                        Some(position) => { script.remove(position); }
+                        None => {
+                            script.push(switch);
+                            script.sort();
+                        }
+                    }
+                }
+            }
+
+            Some(solution)
+        } else { None };
+
+        Instance { switches: self.switches, scripts, planted }
+    }
+}
+
+impl Instance {
+    pub fn system(&self) -> System {
+        let mut system = System::new(self.switches);
+        for script in &self.scripts {
+            system.add_script(script);
+        }
+        system
+    }
+
+    /// The instance in the `N M` input format
+    pub fn to_input(&self) -> String {
+        let mut toggles: Vec<Vec<usize>> = vec![Vec::new(); self.switches];
+        for (index, script) in self.scripts.iter().enumerate() {
+            for switch in script {
+                toggles[*switch as usize - 1].push(index + 1);
+            }
+        }
+
+        let mut input = format!("{} {}\n", self.switches, self.scripts.len());
+        for scripts in toggles {
+            input.push_str(&scripts.len().to_string());
+            for script in scripts {
+                input.push_str(&format!(" {}", script));
+            }
+            input.push('\n');
+        }
+
+        input
+    }
+}
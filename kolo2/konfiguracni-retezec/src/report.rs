@@ -0,0 +1,135 @@
+use std::time::{Duration, Instant};
+use crate::elimination::Elimination;
+use crate::names::Names;
+use crate::{bool_vec_to_string, Equation, Expression, System, Value};
+
+/// Machine readable result of the whole system, see `to_json` for the schema
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub switches: usize,
+    pub scripts: usize,
+    pub sat: bool,
+    pub count: String,
+    pub witness: Option<Vec<bool>>,
+    pub rank: usize,
+    pub free_variables: Vec<i32>,
+    pub backbone: Vec<(i32, bool)>,
+    /// scripts (numbered from 1) adding up to `0 = 1`, empty when satisfiable
+    pub conflict: Vec<usize>,
+    /// assumptions taking part in the conflict
+    pub conflict_assumptions: Vec<(i32, bool)>,
+    pub names: Names,
+    pub time: Duration,
+}
+
+impl Report {
+    pub fn new(system: &System) -> Self {
+        let start = Instant::now();
+        let elimination = Elimination::new(system.switches, &system.equations);
+
+        let conflict = match &elimination.conflict {
+            Some(row) => row.origin(),
+            None => Vec::new(),
+        };
+        let (scripts, assumptions): (Vec<usize>, Vec<usize>) =
+            conflict.into_iter().partition(|index| *index < system.scripts());
+
+        Self {
+            switches: system.switches,
+            scripts: system.scripts(),
+            sat: elimination.is_sat(),
+            count: elimination.count(),
+            witness: elimination.witness(),
+            rank: elimination.rank(),
+            free_variables: if elimination.is_sat() { elimination.free_variables() } else { Vec::new() },
+            backbone: elimination.backbone(),
+            conflict: scripts.iter().map(|index| index + 1).collect(),
+            conflict_assumptions: assumptions.iter().map(|index| assumption(&system.equations[*index])).collect(),
+            names: system.names.clone(),
+            time: start.elapsed(),
+        }
+    }
+
+    /// ```text
+    /// {"status":"sat"|"unsat","switches":N,"scripts":M,"count":"<decimal>","witness":"<bits>"|null,
+    ///  "witness_names":{"<switch name>":0|1,..}|null,"rank":R,"free_variables":[..],"free_variable_names":[..],
+    ///  "backbone":[{"switch":S,"name":"<switch name>","value":0|1},..],"conflict":[..],"conflict_names":[..],
+    ///  "conflict_assumptions":[{"switch":S,"name":"<switch name>","value":0|1},..],"time_us":T}
+    /// ```
+    /// `count` is a string because it doesn't fit into JSON numbers for bigger systems.
+    /// `witness` comes from the elimination with every free variable set to 0 (unless `--witness` picks
+    /// another order), so it can differ from the solution the text output prints.
+    /// Unnamed switches are called `i_N`, unnamed scripts by their number.
+    pub fn to_json(&self) -> String {
+        let switch_value = |(switch, value): &(i32, bool)| {
+            format!("{{\"switch\":{},\"name\":{},\"value\":{}}}", switch, string(&self.names.switch(*switch)), *value as u8)
+        };
+        let backbone: Vec<String> = self.backbone.iter().map(switch_value).collect();
+        let conflict_assumptions: Vec<String> = self.conflict_assumptions.iter().map(switch_value).collect();
+
+        let free_variable_names: Vec<String> = self.free_variables.iter().map(|switch| self.names.switch(*switch)).collect();
+        let conflict_names: Vec<String> = self.conflict.iter().map(|script| self.names.script(*script)).collect();
+
+        format!(
+            "{{\"status\":\"{}\",\"switches\":{},\"scripts\":{},\"count\":\"{}\",\"witness\":{},\"witness_names\":{},\"rank\":{},\"free_variables\":[{}],\"free_variable_names\":[{}],\"backbone\":[{}],\"conflict\":[{}],\"conflict_names\":[{}],\"conflict_assumptions\":[{}],\"time_us\":{}}}",
+            if self.sat { "sat" } else { "unsat" },
+            self.switches,
+            self.scripts,
+            self.count,
+            match &self.witness {
+                Some(witness) => format!("\"{}\"", bool_vec_to_string(witness)),
+                None => "null".to_owned(),
+            },
+            match &self.witness {
+                Some(witness) => {
+                    let values: Vec<String> = witness
+                        .iter()
+                        .enumerate()
+                        .map(|(index, value)| format!("{}:{}", string(&self.names.switch(index as i32 + 1)), *value as u8))
+                        .collect();
+                    format!("{{{}}}", values.join(","))
+                }
+                None => "null".to_owned(),
+            },
+            self.rank,
+            join(&self.free_variables),
+            quote(&free_variable_names),
+            backbone.join(","),
+            join(&self.conflict),
+            quote(&conflict_names),
+            conflict_assumptions.join(","),
+            self.time.as_micros(),
+        )
+    }
+}
+
+/// Switch and value of an assumption `i_N = c`
+fn assumption(equation: &Equation) -> (i32, bool) {
+    match (&equation.left, &equation.right) {
+        (Expression::Val(Value::Var(switch)), Expression::Val(value)) => (*switch, *value == Value::True),
+        _ => panic!("assumption has to be i_N = c"),
+    }
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(",")
+}
+
+fn quote(values: &[String]) -> String {
+    values.iter().map(|value| string(value)).collect::<Vec<String>>().join(",")
+}
+
+/// JSON string literal, names from the input can't contain anything to escape but names set through the API can
+pub(crate) fn string(value: &str) -> String {
+    let mut result = String::from("\"");
+    for ch in value.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            ch if (ch as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
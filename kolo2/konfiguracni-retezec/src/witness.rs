@@ -0,0 +1,160 @@
+use crate::elimination::Elimination;
+use crate::random::Rng;
+use crate::Expression::{Val, Xor};
+use crate::Value::Var;
+use crate::{Equation, Expression, System, Value};
+
+/// Most free switches `MinWeight` is willing to go through, it tries 2^free solutions
+pub const MAX_MINWEIGHT_FREE: usize = 24;
+
+/// Which solution to report, switch 1 being the most significant in lexicographic order
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum WitnessOrder {
+    LexMin,
+    LexMax,
+    /// fewest switches on, the lexicographically smallest of those
+    MinWeight,
+    /// uniformly random solution
+    Random(u64),
+}
+
+impl WitnessOrder {
+    /// `lexmin`, `lexmax`, `minweight` or `random` (with the given seed)
+    pub fn parse(order: &str, seed: u64) -> Option<Self> {
+        match order {
+            "lexmin" => Some(Self::LexMin),
+            "lexmax" => Some(Self::LexMax),
+            "minweight" => Some(Self::MinWeight),
+            "random" => Some(Self::Random(seed)),
+            _ => None,
+        }
+    }
+}
+
+/// Solution chosen by `order`, `None` if there is none
+pub fn witness(system: &System, order: WitnessOrder) -> Result<Option<Vec<bool>>, String> {
+    match order {
+        WitnessOrder::LexMin => Ok(extreme(system, false)),
+        WitnessOrder::LexMax => Ok(extreme(system, true)),
+        WitnessOrder::MinWeight => min_weight(system),
+        WitnessOrder::Random(seed) => {
+            let mut rng = Rng::new(seed);
+            Ok(solution(&system.elimination(), |_| rng.chance(0.5)))
+        }
+    }
+}
+
+/// Lexicographically smallest or largest solution. With switches eliminated in reverse order, every pivot
+/// is the last switch of its row, so it depends only on free switches before it. Going from switch 1 up,
+/// free switches can then be set as wanted and the pivots follow, no choice is ever undone.
+fn extreme(system: &System, value: bool) -> Option<Vec<bool>> {
+    let reverse = |expression: &Expression| -> Expression {
+        let flip = |val: &Value| match val {
+            Var(var) => Var(system.switches as i32 + 1 - var),
+            other => other.clone(),
+        };
+        match expression {
+            Val(val) => Val(flip(val)),
+            Xor(vals) => Xor(vals.iter().map(flip).collect()),
+        }
+    };
+    let equations: Vec<Equation> = system
+        .equations
+        .iter()
+        .map(|equation| Equation { left: reverse(&equation.left), right: reverse(&equation.right) })
+        .collect();
+
+    let mut solution = solution(&Elimination::new(system.switches, &equations), |_| value)?;
+    solution.reverse();
+    Some(solution)
+}
+
+/// Solution with free variables set by `free`, called in increasing order of the variables
+fn solution(elimination: &Elimination, mut free: impl FnMut(i32) -> bool) -> Option<Vec<bool>> {
+    if !elimination.is_sat() {
+        return None;
+    }
+
+    let mut solution = vec![false; elimination.variables];
+    let free_variables = elimination.free_variables();
+    for var in &free_variables {
+        solution[*var as usize - 1] = free(*var);
+    }
+    for (row, pivot) in elimination.rows.iter().zip(&elimination.pivots) {
+        solution[*pivot] = row.constant;
+        for var in &free_variables {
+            if row.has(*var as usize - 1) {
+                solution[*pivot] ^= solution[*var as usize - 1]
+            }
+        }
+    }
+
+    Some(solution)
+}
+
+/// Tries every combination of free variables in Gray code order, one direction added per step
+fn min_weight(system: &System) -> Result<Option<Vec<bool>>, String> {
+    let elimination = system.elimination();
+    let mut current = match solution(&elimination, |_| false) {
+        Some(solution) => pack(&solution),
+        None => return Ok(None),
+    };
+
+    let free = elimination.free_variables();
+    if free.len() > MAX_MINWEIGHT_FREE {
+        return Err(format!(
+            "minweight tries every solution, it is limited to {} free switches, the system has {}",
+            MAX_MINWEIGHT_FREE,
+            free.len()
+        ));
+    }
+
+    // direction of free variable `f`: `f` itself and every pivot depending on it
+    let directions: Vec<Vec<u64>> = free
+        .iter()
+        .map(|var| {
+            let mut direction = vec![false; system.switches];
+            direction[*var as usize - 1] = true;
+            for (row, pivot) in elimination.rows.iter().zip(&elimination.pivots) {
+                direction[*pivot] = row.has(*var as usize - 1);
+            }
+            pack(&direction)
+        })
+        .collect();
+
+    let mut best = current.clone();
+    let mut best_weight = weight(&best);
+    for step in 1u64..1 << free.len() {
+        for (word, direction) in current.iter_mut().zip(&directions[step.trailing_zeros() as usize]) {
+            *word ^= direction
+        }
+
+        let current_weight = weight(&current);
+        if current_weight < best_weight || (current_weight == best_weight && lex_less(&current, &best)) {
+            best.copy_from_slice(&current);
+            best_weight = current_weight;
+        }
+    }
+
+    Ok(Some((0..system.switches).map(|index| best[index / 64] >> (index % 64) & 1 == 1).collect()))
+}
+
+fn pack(bits: &[bool]) -> Vec<u64> {
+    let mut words = vec![0u64; bits.len().div_ceil(64)];
+    for (index, bit) in bits.iter().enumerate() {
+        words[index / 64] |= (*bit as u64) << (index % 64)
+    }
+    words
+}
+
+fn weight(words: &[u64]) -> u32 {
+    words.iter().map(|word| word.count_ones()).sum()
+}
+
+/// Lexicographic order with bit 0 of the first word first
+fn lex_less(a: &[u64], b: &[u64]) -> bool {
+    match a.iter().zip(b).find(|(a, b)| a != b) {
+        Some((a, b)) => a >> (a ^ b).trailing_zeros() & 1 == 0,
+        None => false,
+    }
+}
@@ -0,0 +1,45 @@
+mod common;
+use common::{run, write_input};
+
+const SAMPLE: &str = "3 2\n1 1\n2 1 2\n2 1 2\nnames\nswitch 3 fan\n";
+
+#[test]
+fn count_and_witness_under_assumptions() {
+    let input = write_input("sat", SAMPLE);
+
+    assert_eq!(run(&[input.to_str().unwrap(), "--assume", "2=1"]), "1\ni_1=0 i_2=1 fan=0\n");
+    assert_eq!(run(&[input.to_str().unwrap(), "--assume", "fan=1", "--dense"]), "1\ni_1=0 i_2=0 fan=1\n");
+    assert_eq!(run(&[input.to_str().unwrap(), "--assume", "1=0"]), "2\ni_1=0 i_2=0 fan=1\n");
+}
+
+#[test]
+fn contradiction_lists_responsible_assumptions_and_scripts() {
+    let input = write_input("unsat", SAMPLE);
+
+    assert_eq!(
+        run(&[input.to_str().unwrap(), "--assume", "1=0,2=1,fan=1"]),
+        "0\nscript 2: i_2 XOR fan = 1\nassume i_2 = 1\nassume fan = 1\n"
+    );
+    assert_eq!(
+        run(&[input.to_str().unwrap(), "--assume", "1=1"]),
+        "0\nscript 1: i_1 XOR i_2 XOR fan = 1\nscript 2: i_2 XOR fan = 1\nassume i_1 = 1\n"
+    );
+}
+
+#[test]
+fn json_separates_assumptions_from_scripts() {
+    let input = write_input("json", SAMPLE);
+    let json = run(&[input.to_str().unwrap(), "--assume", "1=1", "--format", "json"]);
+
+    assert!(json.contains("\"scripts\":2,"));
+    assert!(json.contains("\"conflict\":[1,2],\"conflict_names\":[\"1\",\"2\"],\"conflict_assumptions\":[{\"switch\":1,\"name\":\"i_1\",\"value\":1}]"));
+}
+
+#[test]
+fn invalid_assumptions() {
+    let input = write_input("invalid", SAMPLE);
+
+    assert_eq!(run(&[input.to_str().unwrap(), "--assume", "4=1"]), "Err: switch 4 doesn't exist\n");
+    assert_eq!(run(&[input.to_str().unwrap(), "--assume", "fan=2"]), "Err: assumption \"fan=2\" has to be <switch>=0|1\n");
+    assert_eq!(run(&[input.to_str().unwrap(), "--assume", "pump=1"]), "Err: unknown switch \"pump\"\n");
+}
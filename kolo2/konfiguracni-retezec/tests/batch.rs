@@ -0,0 +1,108 @@
+use konfiguracni_retezec::batch::map_parallel;
+use konfiguracni_retezec::random::{Generator, Rng};
+
+mod common;
+use common::{output, run, write_input};
+
+/// Stdout of a batch where some instances fail, which exits with 1
+fn run_failing(args: &[&str]) -> String {
+    let output = output(args);
+    assert_eq!(output.status.code(), Some(1));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn batch_matches_single_runs() {
+    let mut rng = Rng::new(36);
+    let mut batch = "40\n".to_owned();
+    let mut expected = String::new();
+
+    for index in 0..40 {
+        let generator = Generator {
+            switches: 1 + rng.below(10) as usize,
+            scripts: 1 + rng.below(10) as usize,
+            density: 0.3,
+            planted: rng.chance(0.5),
+        };
+        let input = generator.generate(&mut rng).to_input();
+
+        batch.push_str(&input);
+        let single = write_input(&format!("single-{}", index), &input);
+        expected.push_str(&run(&[single.to_str().unwrap()]));
+    }
+
+    let batch = write_input("random", &batch);
+    for threads in ["1", "4"] {
+        assert_eq!(run(&["batch", batch.to_str().unwrap(), "--threads", threads]), expected);
+    }
+}
+
+#[test]
+fn malformed_blocks_are_isolated() {
+    // switch lines are read as leniently as in a single system, a block only fails on what fails there
+    let input = write_input(
+        "malformed",
+        "5\n3 2\n1 1\n2 1 2\n2 1 2\n2 1\n1 1\n1 2\n2 1\n1 1\n0\nnames\nswitch 7 fan\n2 1\n1 1\n1 1\nx y\n1 1\n",
+    );
+
+    assert_eq!(
+        run_failing(&["batch", input.to_str().unwrap()]),
+        "2\n001\n\
+         2\n10\n\
+         Err: instance 3: switch 7 doesn't exist\n\
+         2\n01\n\
+         Err: instance 5: failed to parse first line \"x y\"\n"
+    );
+
+    let truncated = write_input("truncated", "3\n1 1\n1 1\n2 1\n1 1\n");
+    assert_eq!(
+        run_failing(&["batch", truncated.to_str().unwrap()]),
+        "1\n1\nErr: instance 2: expected 2 switch lines, found 1\nErr: instance 3: missing, the file ends before it\n"
+    );
+}
+
+#[test]
+fn short_block_keeps_the_next_header() {
+    // the first block lacks a switch line, `3 1` can't be one and starts the second block
+    let input = write_input("short", "3\n2 1\n1 1\n3 1\n1 1\n0\n0\nnames\nswitch 1 fan\n2 1\n1 1\n1 1\n");
+    assert_eq!(
+        run_failing(&["batch", input.to_str().unwrap()]),
+        "Err: instance 1: expected 2 switch lines, found 1\n4\nfan=1 i_2=0 i_3=0\n2\n01\n"
+    );
+
+    // so does a short block with a names section
+    let input = write_input("short-names", "2\n2 1\n1 1\nnames\nswitch 1 fan\n1 1\n1 1\n");
+    assert_eq!(run_failing(&["batch", input.to_str().unwrap()]), "Err: instance 1: expected 2 switch lines, found 1\n1\n1\n");
+}
+
+#[test]
+fn names_assumptions_and_json() {
+    let input = write_input("names", "2\n2 1\n1 1\n1 1\nnames\nswitch 1 power\n2 1\n1 1\n0\n");
+
+    assert_eq!(run(&["batch", input.to_str().unwrap(), "--assume", "1=0"]), "1\npower=0 i_2=1\n0\nscript 1: i_1 = 1\nassume i_1 = 0\n");
+
+    let json = run(&["batch", input.to_str().unwrap(), "--format", "json"]);
+    let lines: Vec<&str> = json.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("{\"status\":\"sat\",\"switches\":2,\"scripts\":1,\"count\":\"2\""));
+    assert!(lines[1].starts_with("{\"status\":\"sat\",\"switches\":2,\"scripts\":1,\"count\":\"2\",\"witness\":\"10\""));
+}
+
+#[test]
+fn rejects_single_system_outputs() {
+    let input = write_input("outputs", "1\n1 1\n1 1\n");
+    for [option, value] in [["--format", "dot"], ["--proof", "proof.txt"]] {
+        let output = output(&["batch", input.to_str().unwrap(), option, value]);
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8(output.stderr).unwrap().starts_with("batch can't be combined with --format dot or --proof"));
+    }
+}
+
+#[test]
+fn map_parallel_keeps_order() {
+    let items: Vec<u64> = (0..100).collect();
+    for threads in [1, 3, 200] {
+        assert_eq!(map_parallel(&items, threads, |item| item * item), items.iter().map(|item| item * item).collect::<Vec<_>>());
+    }
+    assert!(map_parallel(&[] as &[u64], 4, |item| *item).is_empty());
+}
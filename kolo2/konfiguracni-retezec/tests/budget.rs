@@ -0,0 +1,43 @@
+
+mod common;
+use common::{output, write_input};
+
+// 4x4 torus with a single lit cell, the search needs thousands of nodes for it
+const TORUS: &str = "4 4 torus\n3 3\n.#.\n###\n.#.\n#...\n....\n....\n....\n";
+
+#[test]
+fn max_nodes_stops_search() {
+    let input = write_input("nodes", TORUS);
+    let output = output(&["grid", input.to_str().unwrap(), "--max-nodes", "50"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "budget exceeded after 50 nodes\n>=0\n");
+}
+
+#[test]
+fn timeout_stops_search() {
+    let input = write_input("timeout", TORUS);
+    let output = output(&["grid", input.to_str().unwrap(), "--timeout", "0"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stdout).unwrap().starts_with("budget exceeded after "));
+}
+
+#[test]
+fn partial_result_keeps_found_solutions() {
+    let input = write_input("partial", "3 2\n1 1\n2 1 2\n2 1 2\n");
+
+    // second solution is found only after the first leaf
+    let output = output(&[input.to_str().unwrap(), "--max-nodes", "4"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "budget exceeded after 4 nodes\n>=1\n001\n");
+}
+
+#[test]
+fn enough_budget_is_silent() {
+    let input = write_input("enough", "3 2\n1 1\n2 1 2\n2 1 2\n");
+    let output = output(&[input.to_str().unwrap(), "--max-nodes", "1000", "--timeout", "60"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n001\n");
+}
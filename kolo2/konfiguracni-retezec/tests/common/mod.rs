@@ -0,0 +1,54 @@
+//! Helpers of the tests running the binary, every test file uses only some of them
+#![allow(dead_code)]
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// File in the temp dir, deleted when dropped
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// `name` has to be unique within the test file, other test files run in other processes
+    pub fn new(name: &str) -> Self {
+        Self { path: std::env::temp_dir().join(format!("konfiguracni-retezec-{}-{}", std::process::id(), name)) }
+    }
+}
+
+impl Deref for TempFile {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // the binary may not have written it
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+pub fn write_input(name: &str, contents: &str) -> TempFile {
+    let file = TempFile::new(name);
+    fs::write(&*file, contents).unwrap();
+    file
+}
+
+pub fn output(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_konfiguracni-retezec"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Stdout of a run that has to succeed
+pub fn run(args: &[&str]) -> String {
+    let output = output(args);
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
@@ -0,0 +1,60 @@
+use konfiguracni_retezec::oracle::brute_force;
+use konfiguracni_retezec::random::{Generator, Rng};
+use konfiguracni_retezec::System;
+
+fn check(seed: u64, system: &System, threads: usize) {
+    let expected = system.elimination();
+    let dense = system.dense(threads);
+
+    assert_eq!(dense.rank, expected.rank(), "seed {}: rank", seed);
+    assert_eq!(dense.consistent, expected.is_sat(), "seed {}: consistency", seed);
+    assert_eq!(dense.count(), expected.count(), "seed {}: count", seed);
+
+    match dense.witness() {
+        Some(witness) => assert!(system.equations.iter().all(|eq| eq.holds(&witness)), "seed {}: witness", seed),
+        None => assert!(!expected.is_sat(), "seed {}: missing witness", seed)
+    }
+}
+
+#[test]
+fn small_systems_agree_with_brute_force() {
+    for seed in 0..1000 {
+        let mut rng = Rng::new(seed);
+        let generator = Generator {
+            switches: 1 + rng.below(16) as usize,
+            scripts: 1 + rng.below(20) as usize,
+            density: [0.2, 0.5, 0.8][rng.below(3) as usize],
+            planted: rng.chance(0.5),
+        };
+        let system = generator.generate(&mut rng).system();
+
+        assert_eq!(system.dense(2).count(), brute_force(&system).len().to_string(), "seed {}", seed);
+        check(seed, &system, 1 + rng.below(4) as usize);
+    }
+}
+
+#[test]
+fn big_systems_agree_with_elimination() {
+    // wider than one cache block of words, with more and less scripts than switches
+    for (seed, switches, scripts) in [(1, 2100, 2000), (2, 700, 900), (3, 1000, 300), (4, 130, 130)] {
+        let mut rng = Rng::new(seed);
+        for planted in [true, false] {
+            let generator = Generator { switches, scripts, density: 0.5, planted };
+            let system = generator.generate(&mut rng).system();
+
+            check(seed, &system, 1);
+            check(seed, &system, 4);
+        }
+    }
+}
+
+#[test]
+fn sparse_rank_deficient_system() {
+    let mut rng = Rng::new(7);
+    let mut system = Generator { switches: 300, scripts: 400, density: 0.01, planted: true }.generate(&mut rng).system();
+    // duplicated scripts don't add to the rank
+    let copy = system.equations.clone();
+    system.equations.extend(copy);
+
+    check(7, &system, 3);
+}
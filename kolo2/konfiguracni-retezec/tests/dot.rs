@@ -0,0 +1,37 @@
+use konfiguracni_retezec::dot::to_dot;
+use konfiguracni_retezec::System;
+
+#[test]
+fn components_and_status() {
+    let mut system = System::parse("4 3\n2 1 2\n2 2 3\n1 3\n0\nnames\nswitch 4 fan\nscript 2 net.up\n").unwrap();
+    let dot = to_dot(&system);
+
+    assert!(dot.starts_with("graph konfiguracni_retezec {\n"));
+    assert!(dot.ends_with("\n}"));
+    assert!(dot.contains(
+        "  subgraph cluster_1 {\n    label=\"component 1\";\n    s1 [label=\"i_1\", fillcolor=palegreen];\n    \
+         s2 [label=\"i_2\", fillcolor=lightgray];\n    s3 [label=\"i_3\", fillcolor=palegreen];\n    c1 [label=\"1\", shape=box];\n    \
+         c2 [label=\"net.up\", shape=box];\n    c3 [label=\"3\", shape=box];\n  }\n"
+    ));
+    assert!(dot.contains("  subgraph cluster_2 {\n    label=\"component 2\";\n    s4 [label=\"fan\", fillcolor=white];\n  }\n"));
+    assert!(dot.contains("  s1 -- c1;\n  s1 -- c2;\n  s2 -- c2;\n  s2 -- c3;\n  s3 -- c3;\n"));
+    assert!(!dot.contains("red"));
+
+    system.assume(4, true);
+    assert!(to_dot(&system).contains("s4 [label=\"fan\", fillcolor=palegreen];"));
+}
+
+#[test]
+fn unsat_core_is_highlighted() {
+    // scripts 1 and 2 are toggled by the same switches, script 3 by their XOR, script 4 is independent
+    let system = System::parse("4 4\n2 1 3\n2 2 3\n2 1 2\n1 4\n").unwrap();
+    let dot = to_dot(&system);
+
+    for script in 1..=3 {
+        assert!(dot.contains(&format!("c{} [label=\"{}\", shape=box, color=red, penwidth=3];", script, script)));
+    }
+    assert!(dot.contains("c4 [label=\"4\", shape=box];"));
+    assert!(dot.contains("  s1 -- c1 [color=red];\n"));
+    assert!(dot.contains("  s4 -- c4;\n"));
+    assert!(!dot.contains("palegreen"));
+}
@@ -0,0 +1,102 @@
+use konfiguracni_retezec::explain::{explain, unsat_core};
+use konfiguracni_retezec::random::{Generator, Rng};
+use konfiguracni_retezec::{Equation, Expression, System, Value};
+
+fn forced(var: i32, value: bool) -> Equation {
+    Equation {
+        left: Expression::Val(Value::Var(var)),
+        right: Expression::Val(if value { Value::True } else { Value::False }),
+    }
+}
+
+/// Whether the scripts (with the negated forced equation) have no solution,
+/// i.e. the forced equation follows from them
+fn implies(system: &System, scripts: &[usize], var: i32, value: bool) -> bool {
+    let mut subsystem = System::new(system.switches);
+    for script in scripts {
+        subsystem.add_equation(system.equations[*script].clone());
+    }
+    subsystem.add_equation(forced(var, !value));
+    !subsystem.elimination().is_sat()
+}
+
+#[test]
+fn step_by_step_text() {
+    let system = System::parse("4 4\n2 1 2\n3 1 3 4\n2 2 3\n2 3 4\nnames\nswitch 4 fan\nscript 2 boot\n").unwrap();
+
+    assert_eq!(
+        explain(&system, 1).unwrap().to_text(&system),
+        "i_1 = 1 follows from 3 script(s):\n\
+         \x20 script boot: i_1 XOR i_3 = 1\n\
+         + script 3: i_2 XOR i_3 XOR fan = 1\n\
+         \x20 = i_1 XOR i_2 XOR fan = 0\n\
+         + script 4: i_2 XOR fan = 1\n\
+         \x20 = i_1 = 1"
+    );
+}
+
+#[test]
+fn not_forced() {
+    let system = System::parse("3 2\n1 1\n2 1 2\n2 1 2\n").unwrap();
+
+    assert_eq!(explain(&system, 1).unwrap().scripts, vec![0, 1]);
+    assert_eq!(explain(&system, 2), Err("i_2 is not forced".to_owned()));
+    assert_eq!(explain(&system, 4), Err("switch 4 doesn't exist".to_owned()));
+}
+
+#[test]
+fn explanations_are_minimal() {
+    for seed in 0..300 {
+        let mut rng = Rng::new(seed);
+        let generator = Generator {
+            switches: 1 + rng.below(10) as usize,
+            scripts: 1 + rng.below(16) as usize,
+            density: [0.2, 0.4][rng.below(2) as usize],
+            planted: true,
+        };
+        let system = generator.generate(&mut rng).system();
+
+        for (var, value) in system.elimination().backbone() {
+            let explanation = explain(&system, var).unwrap();
+            assert_eq!(explanation.value, value);
+            assert!(implies(&system, &explanation.scripts, var, value), "seed {}: {} doesn't follow", seed, var);
+
+            for left_out in &explanation.scripts {
+                let rest: Vec<usize> = explanation.scripts.iter().copied().filter(|s| s != left_out).collect();
+                assert!(!implies(&system, &rest, var, value), "seed {}: {} isn't minimal", seed, var);
+            }
+        }
+    }
+}
+
+#[test]
+fn unsat_cores_are_minimal() {
+    for seed in 0..300 {
+        let mut rng = Rng::new(seed);
+        let generator = Generator { switches: 1 + rng.below(8) as usize, scripts: 1 + rng.below(12) as usize, density: 0.3, planted: false };
+        let mut system = generator.generate(&mut rng).system();
+        system.assume(1, rng.chance(0.5));
+
+        let core = match unsat_core(&system) {
+            Some(core) => core,
+            None => {
+                assert!(system.elimination().is_sat());
+                continue;
+            }
+        };
+
+        let subsystem = |indices: &[usize]| {
+            let mut subsystem = System::new(system.switches);
+            for index in indices {
+                subsystem.equations.push(system.equations[*index].clone());
+            }
+            subsystem
+        };
+
+        assert!(!subsystem(&core).elimination().is_sat(), "seed {}: core has a solution", seed);
+        for left_out in &core {
+            let rest: Vec<usize> = core.iter().copied().filter(|index| index != left_out).collect();
+            assert!(subsystem(&rest).elimination().is_sat(), "seed {}: core isn't minimal", seed);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+mod common;
+use common::{run, write_input};
+
+/// Drops the `time_us` field, the only one that isn't deterministic
+fn without_time(json: &str) -> String {
+    let start = json.find(",\"time_us\":").expect("missing time_us");
+    let end = json[start..].find('}').unwrap() + start;
+    assert!(json[start + 11..end].parse::<u128>().is_ok());
+    format!("{}{}", &json[..start], &json[end..])
+}
+
+#[test]
+fn sat_schema() {
+    let input = write_input("sat", "3 2\n1 1\n2 1 2\n2 1 2\n");
+    let json = run(&[input.to_str().unwrap(), "--format", "json"]);
+
+    assert_eq!(
+        without_time(json.trim()),
+        "{\"status\":\"sat\",\"switches\":3,\"scripts\":2,\"count\":\"2\",\"witness\":\"010\",\
+         \"witness_names\":{\"i_1\":0,\"i_2\":1,\"i_3\":0},\"rank\":2,\"free_variables\":[3],\"free_variable_names\":[\"i_3\"],\
+         \"backbone\":[{\"switch\":1,\"name\":\"i_1\",\"value\":0}],\"conflict\":[],\"conflict_names\":[],\"conflict_assumptions\":[]}"
+    );
+}
+
+#[test]
+fn unsat_reports_conflicting_scripts() {
+    // scripts 1 and 2 are toggled by the same switches, script 3 by their XOR
+    let input = write_input("unsat", "3 3\n2 1 3\n2 2 3\n2 1 2\n");
+    let json = run(&["--format", "json", input.to_str().unwrap()]);
+
+    assert_eq!(
+        without_time(json.trim()),
+        "{\"status\":\"unsat\",\"switches\":3,\"scripts\":3,\"count\":\"0\",\"witness\":null,\"witness_names\":null,\
+         \"rank\":2,\"free_variables\":[],\"free_variable_names\":[],\"backbone\":[],\"conflict\":[1,2,3],\"conflict_names\":[\"1\",\"2\",\"3\"],\
+         \"conflict_assumptions\":[]}"
+    );
+}
+
+#[test]
+fn count_is_exact_beyond_integer_range() {
+    // 100 switches, single script toggled by the first one
+    let mut contents = "100 1\n1 1\n".to_owned();
+    for _ in 1..100 {
+        contents.push_str("0\n");
+    }
+    let input = write_input("big", &contents);
+    let json = run(&[input.to_str().unwrap(), "--format", "json"]);
+
+    // 2^99
+    assert!(json.contains("\"count\":\"633825300114114700748351602688\""));
+    assert!(json.contains("\"backbone\":[{\"switch\":1,\"name\":\"i_1\",\"value\":1}]"));
+}
+
+#[test]
+fn names_are_used() {
+    let input = write_input(
+        "names",
+        "3 3\n2 1 3\n2 2 3\n2 1 2\nnames\nswitch 1 power\nswitch 3 fan\nscript 1 boot\nscript 3 net.up\n",
+    );
+    let json = run(&[input.to_str().unwrap(), "--format", "json"]);
+
+    assert!(json.contains("\"conflict\":[1,2,3],\"conflict_names\":[\"boot\",\"2\",\"net.up\"]"));
+
+    let input = write_input("names-sat", "3 2\n1 1\n2 1 2\n2 1 2\nnames\nswitch 1 power\nswitch 3 fan\n");
+    let json = run(&[input.to_str().unwrap(), "--format", "json"]);
+
+    assert!(json.contains("\"witness\":\"010\",\"witness_names\":{\"power\":0,\"i_2\":1,\"fan\":0}"));
+    assert!(json.contains("\"free_variable_names\":[\"fan\"]"));
+    assert!(json.contains("\"backbone\":[{\"switch\":1,\"name\":\"power\",\"value\":0}]"));
+}
+
+#[test]
+fn grid_schema() {
+    let input = write_input("grid", "3 3 rect\n3 3\n.#.\n###\n.#.\n#.#\n...\n#.#\n");
+    let json = run(&["grid", input.to_str().unwrap(), "--format", "json"]);
+
+    assert!(json.starts_with("{\"status\":\"sat\",\"switches\":9,\"scripts\":9,\"count\":\"1\",\"witness\":\"101000101\","));
+    assert!(json.contains("\"rank\":9,"));
+}
+
+#[test]
+fn text_is_default() {
+    let input = write_input("text", "3 2\n1 1\n2 1 2\n2 1 2\n");
+    assert_eq!(run(&[input.to_str().unwrap()]), "2\n001\n");
+    assert_eq!(run(&[input.to_str().unwrap(), "--format", "text"]), "2\n001\n");
+}
+
+#[test]
+fn witness_sets_free_switches_to_zero() {
+    // i_3 is free: the text output prints the first solution of the search, JSON the one of the elimination
+    let input = write_input("free", "3 2\n1 1\n2 1 2\n2 1 2\n");
+    assert_eq!(run(&[input.to_str().unwrap()]), "2\n001\n");
+    assert!(run(&[input.to_str().unwrap(), "--format", "json"]).contains("\"witness\":\"010\","));
+}
@@ -0,0 +1,170 @@
+use konfiguracni_retezec::grid::Grid;
+use konfiguracni_retezec::{bool_vec_to_string, Budget, Equation, Expression, System, Value};
+
+fn sample() -> System {
+    System::parse("3 2\n1 1\n2 1 2\n2 1 2\n").unwrap()
+}
+
+#[test]
+fn parse_builds_one_equation_per_script() {
+    let system = sample();
+
+    let mut expected = System::new(3);
+    expected.add_script(&[1, 2, 3]).add_script(&[2, 3]);
+
+    assert_eq!(system, expected);
+    assert_eq!(system.equations[1].to_string(), "i_2 XOR i_3 = 1");
+}
+
+#[test]
+fn parse_rejects_bad_header() {
+    assert_eq!(System::parse("x 2\n"), Err("failed to parse first line".to_owned()));
+    assert_eq!(System::parse("3\n"), Err("failed to parse first line".to_owned()));
+}
+
+#[test]
+fn parse_rejects_switch_lines_beyond_n() {
+    assert_eq!(System::parse("2 1\n1 1\n1 1\n1 1\n"), Err("switch line 3 is beyond the 2 switches".to_owned()));
+    assert!(System::parse("2 1\n1 1\n1 1\n\n").is_ok());
+}
+
+#[test]
+#[should_panic(expected = "switch 3 is not one of the 2 switches")]
+fn elimination_rejects_unknown_switch() {
+    let mut system = System::new(2);
+    system.add_script(&[1, 3]);
+    system.elimination();
+}
+
+#[test]
+fn switch_listed_twice_cancels_out() {
+    let mut system = System::new(2);
+    system.add_script(&[1, 2, 1]);
+    assert_eq!(system.count(), "2");
+    assert_eq!(system.search(&mut Budget::unlimited()), Some((2, "01".to_owned())));
+}
+
+#[test]
+fn search_count_and_solutions_agree() {
+    let system = sample();
+
+    assert_eq!(system.search(&mut Budget::unlimited()), Some((2, "001".to_owned())));
+    assert_eq!(system.count(), "2");
+
+    let mut solutions: Vec<String> = system.solutions().map(|solution| bool_vec_to_string(&solution)).collect();
+    solutions.sort();
+    assert_eq!(solutions, vec!["001", "010"]);
+}
+
+#[test]
+fn equations_with_constants() {
+    let mut system = System::new(2);
+    system
+        .add_equation(Equation {
+            left: Expression::Xor(vec![Value::Var(1), Value::Var(2)]),
+            right: Expression::Val(Value::False),
+        })
+        .add_equation(Equation {
+            left: Expression::Val(Value::Var(1)),
+            right: Expression::Val(Value::True),
+        });
+
+    let elimination = system.elimination();
+    assert_eq!(elimination.rank(), 2);
+    assert_eq!(elimination.backbone(), vec![(1, true), (2, true)]);
+    assert_eq!(system.solutions().collect::<Vec<_>>(), vec![vec![true, true]]);
+}
+
+#[test]
+fn unsat_system() {
+    let mut system = System::new(3);
+    system.add_script(&[1, 3]).add_script(&[2, 3]).add_script(&[1, 2]);
+
+    assert_eq!(system.count(), "0");
+    assert_eq!(system.solutions().next(), None);
+    assert_eq!(system.search(&mut Budget::unlimited()), None);
+    assert_eq!(system.elimination().conflict.unwrap().origin(), vec![0, 1, 2]);
+}
+
+#[test]
+fn solutions_are_distinct_and_valid() {
+    let mut system = System::new(6);
+    system.add_script(&[1, 2, 3]).add_script(&[3, 4]).add_script(&[5]);
+
+    let solutions: Vec<Vec<bool>> = system.solutions().collect();
+    assert_eq!(solutions.len(), 8);
+    assert_eq!(system.count(), "8");
+
+    for solution in &solutions {
+        assert!(solution[0] ^ solution[1] ^ solution[2]);
+        assert!(solution[2] ^ solution[3]);
+        assert!(solution[4]);
+    }
+
+    let mut distinct = solutions.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), solutions.len());
+}
+
+#[test]
+fn grid_system() {
+    let grid = Grid::parse("5 5 rect\n3 3\n.#.\n###\n.#.\n#####\n#####\n#####\n#####\n#####\n").unwrap();
+    let system = grid.system();
+
+    assert_eq!(system.count(), "4");
+    let (solutions, witness) = system.search(&mut Budget::unlimited()).unwrap();
+    assert_eq!(solutions, 4);
+    assert_eq!(grid.render(&witness), "...##\n##.##\n###..\n.###.\n#.##.");
+}
+
+#[test]
+fn torus_mask_wider_than_grid() {
+    // on a 2x2 torus the 3x3 mask covers the other row and column twice, so a press only toggles its own cell
+    let grid = Grid::parse("2 2 torus\n3 3\n###\n###\n###\n#.\n..\n").unwrap();
+    let system = grid.system();
+
+    assert_eq!(system.equations[0].to_string(), "i_1 = 1");
+    assert_eq!(system.count(), "1");
+    let (solutions, witness) = system.search(&mut Budget::unlimited()).unwrap();
+    assert_eq!(solutions, 1);
+    assert_eq!(grid.render(&witness), "#.\n..");
+}
+
+#[test]
+fn search_respects_budget() {
+    let mut budget = Budget::new(None, Some(3));
+    assert_eq!(sample().search(&mut budget), None);
+    assert!(budget.exceeded());
+    assert_eq!(budget.nodes(), 3);
+}
+
+#[test]
+fn names_section() {
+    let system = System::parse("3 2\n1 1\n2 1 2\n2 1 2\nnames\nswitch 2 fan\nscript 1 boot\n").unwrap();
+
+    // symbol table lines aren't switch lines
+    assert_eq!(system.equations, sample().equations);
+    assert_eq!(system.equations[1].named(&system.names), "fan XOR i_3 = 1");
+    assert_eq!(system.equations[1].to_string(), "i_2 XOR i_3 = 1");
+    assert_eq!(system.names.script(1), "boot");
+    assert_eq!(system.names.script(2), "2");
+    assert_eq!(system.names.assignment(&[false, true, false]), "i_1=0 fan=1 i_3=0");
+}
+
+#[test]
+fn invalid_names() {
+    for (symbols, error) in [
+        ("switch 4 x", "switch 4 doesn't exist"),
+        ("script 0 x", "script 0 doesn't exist"),
+        ("switch 1 a\nswitch 2 a", "name \"a\" is used twice"),
+        ("switch 1 a\nswitch 1 b", "switch 1 is named twice"),
+        ("switch 1 i_2", "\"i_2\" can't be used as a name"),
+        ("script 1 7", "\"7\" can't be used as a name"),
+        ("lamp 1 x", "unknown kind \"lamp\" in name line"),
+        ("switch 1", "name line \"switch 1\" has to be \"switch|script <index> <name>\""),
+    ] {
+        let input = format!("3 2\n1 1\n2 1 2\n2 1 2\nnames\n{}\n", symbols);
+        assert_eq!(System::parse(&input), Err(error.to_owned()), "{}", symbols);
+    }
+}
@@ -0,0 +1,73 @@
+use konfiguracni_retezec::oracle::brute_force;
+use konfiguracni_retezec::random::{Generator, Rng};
+use konfiguracni_retezec::{bool_vec_to_string, Budget, System};
+
+const SEEDS: u64 = 3000;
+
+fn random_generator(rng: &mut Rng) -> Generator {
+    Generator {
+        switches: 1 + rng.below(12) as usize,
+        scripts: 1 + rng.below(12) as usize,
+        density: [0.1, 0.3, 0.5, 0.8][rng.below(4) as usize],
+        planted: rng.chance(0.5),
+    }
+}
+
+fn check(seed: u64, system: &System) {
+    let expected = brute_force(system);
+
+    // elimination
+    assert_eq!(system.count(), expected.len().to_string(), "seed {}: count", seed);
+    let elimination = system.elimination();
+    assert_eq!(elimination.is_sat(), !expected.is_empty(), "seed {}: unsat detection", seed);
+    if let Some(conflict) = &elimination.conflict {
+        let mut core = System::new(system.switches);
+        for index in conflict.origin() {
+            core.add_equation(system.equations[index].clone());
+        }
+        assert!(brute_force(&core).is_empty(), "seed {}: conflict is satisfiable", seed);
+    }
+    let mut solutions: Vec<Vec<bool>> = system.solutions().collect();
+    solutions.sort();
+    assert_eq!(solutions, expected, "seed {}: enumerated solutions", seed);
+
+    // search
+    match system.search(&mut Budget::unlimited()) {
+        Some((count, witness)) => {
+            assert_eq!(count as usize, expected.len(), "seed {}: search count", seed);
+            let witness: Vec<bool> = witness.chars().map(|ch| ch == '1').collect();
+            assert!(system.equations.iter().all(|eq| eq.holds(&witness)), "seed {}: invalid witness", seed);
+        }
+        None => assert!(expected.is_empty(), "seed {}: search missed solutions", seed)
+    }
+}
+
+#[test]
+fn solvers_agree_with_brute_force() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let generator = random_generator(&mut rng);
+        let instance = generator.generate(&mut rng);
+        let system = instance.system();
+
+        check(seed, &system);
+
+        if let Some(planted) = &instance.planted {
+            assert!(system.equations.iter().all(|eq| eq.holds(planted)), "seed {}: planted solution", seed);
+        }
+    }
+}
+
+#[test]
+fn parsed_input_matches_instance() {
+    for seed in 0..100 {
+        let mut rng = Rng::new(seed);
+        let instance = random_generator(&mut rng).generate(&mut rng);
+
+        let parsed = System::parse(&instance.to_input()).unwrap();
+        for (parsed, generated) in parsed.solutions().zip(instance.system().solutions()) {
+            assert_eq!(bool_vec_to_string(&parsed), bool_vec_to_string(&generated), "seed {}", seed);
+        }
+        assert_eq!(parsed.count(), instance.system().count(), "seed {}", seed);
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::BTreeSet;
+use konfiguracni_retezec::oracle::brute_force;
+use konfiguracni_retezec::project::project;
+use konfiguracni_retezec::random::{Generator, Rng};
+use konfiguracni_retezec::System;
+
+mod common;
+use common::{run, write_input};
+
+#[test]
+fn projections_agree_with_brute_force() {
+    for seed in 0..1000 {
+        let mut rng = Rng::new(seed);
+        let generator = Generator {
+            switches: 1 + rng.below(10) as usize,
+            scripts: 1 + rng.below(10) as usize,
+            density: [0.2, 0.5][rng.below(2) as usize],
+            planted: rng.chance(0.7),
+        };
+        let system = generator.generate(&mut rng).system();
+        let switches: Vec<i32> = (1..=system.switches as i32).filter(|_| rng.chance(0.5)).collect();
+
+        let expected: BTreeSet<Vec<bool>> = brute_force(&system)
+            .iter()
+            .map(|solution| switches.iter().map(|switch| solution[*switch as usize - 1]).collect())
+            .collect();
+
+        match project(&system, &switches) {
+            Some(projection) => {
+                assert_eq!(projection.count(), expected.len().to_string(), "seed {}: count", seed);
+                let assignments: Vec<Vec<bool>> = projection.assignments().collect();
+                assert_eq!(assignments.len(), expected.len(), "seed {}: duplicate assignments", seed);
+                assert_eq!(assignments.into_iter().collect::<BTreeSet<_>>(), expected, "seed {}: assignments", seed);
+            }
+            None => assert!(expected.is_empty(), "seed {}: missed solutions", seed)
+        }
+    }
+}
+
+#[test]
+fn switch_lists() {
+    let system = System::parse("9 0\n0\n0\n0\n0\n0\n0\n0\n0\n0\nnames\nswitch 3 fan-in\n").unwrap();
+
+    assert_eq!(system.switch_list("1,2,5-7").unwrap(), vec![1, 2, 5, 6, 7]);
+    assert_eq!(system.switch_list("fan-in, 2-4, 1").unwrap(), vec![3, 2, 4, 1]);
+    assert_eq!(system.switch_list("7-5").unwrap_err(), "range \"7-5\" is empty");
+    assert_eq!(system.switch_list("8-10").unwrap_err(), "switch 10 doesn't exist");
+    assert_eq!(system.switch_list("fan").unwrap_err(), "unknown switch \"fan\"");
+}
+
+#[test]
+fn cli() {
+    // i_1 = 1, i_2 = 0, i_3 = 1, i_4 is free
+    let input = write_input("cli", "4 3\n2 1 2\n2 2 3\n1 3\n0\n");
+
+    assert_eq!(run(&[input.to_str().unwrap(), "--project", "1-3"]), "1\n");
+    assert_eq!(run(&[input.to_str().unwrap(), "--project", "4,1", "--enumerate"]), "2\n01\n11\n");
+    assert_eq!(
+        run(&[input.to_str().unwrap(), "--project", "3-4", "--enumerate", "--format", "json"]).trim(),
+        "{\"switches\":[3,4],\"switch_names\":[\"i_3\",\"i_4\"],\"dimension\":1,\"count\":\"2\",\"assignments\":[\"10\",\"11\"]}"
+    );
+    assert_eq!(run(&[input.to_str().unwrap(), "--project", "4", "--assume", "4=1"]), "1\n");
+    assert_eq!(run(&[input.to_str().unwrap(), "--project", "4", "--assume", "1=0"]), "0\n");
+
+    let named = write_input("named", "4 3\n2 1 2\n2 2 3\n1 3\n0\nnames\nswitch 1 power\nswitch 4 fan\n");
+    assert_eq!(run(&[named.to_str().unwrap(), "--project", "fan,power", "--enumerate"]), "2\nfan=0 power=1\nfan=1 power=1\n");
+}
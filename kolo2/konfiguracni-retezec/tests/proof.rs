@@ -0,0 +1,80 @@
+use std::process::Command;
+use konfiguracni_retezec::check::check;
+use konfiguracni_retezec::proof::to_proof;
+use konfiguracni_retezec::random::{Generator, Rng};
+use konfiguracni_retezec::System;
+
+mod common;
+use common::{write_input, TempFile};
+
+const UNSAT: &str = "3 3\n2 1 3\n2 2 3\n2 1 2\n";
+
+#[test]
+fn proofs_of_random_instances_check() {
+    let mut proofs = 0;
+    for seed in 0..500 {
+        let mut rng = Rng::new(seed);
+        let generator = Generator {
+            switches: 1 + rng.below(8) as usize,
+            scripts: 1 + rng.below(12) as usize,
+            density: [0.2, 0.5][rng.below(2) as usize],
+            planted: false,
+        };
+        let instance = generator.generate(&mut rng);
+        let system = instance.system();
+
+        match to_proof(&system) {
+            Some(proof) => {
+                assert!(check(&instance.to_input(), &proof).is_ok(), "seed {}: {}", seed, proof);
+                proofs += 1;
+            }
+            None => assert!(system.elimination().is_sat(), "seed {}: missing proof", seed),
+        }
+    }
+    assert!(proofs > 50);
+}
+
+#[test]
+fn wrong_proofs_are_rejected() {
+    let proof = to_proof(&System::parse(UNSAT).unwrap()).unwrap();
+    assert_eq!(
+        proof,
+        "steps 3\n1: script 1 => i_1 XOR i_3 = 1\n2: 1 + script 2 => i_1 XOR i_2 = 0\n3: 2 + script 3 => 0 = 1"
+    );
+    assert_eq!(check(UNSAT, &proof), Ok(3));
+
+    let cases = [
+        (proof.replace("i_1 XOR i_2 = 0", "i_1 = 0"), "step 2: \"i_1 = 0\" doesn't follow"),
+        (proof.replace("2 + script 3", "1 + script 3"), "step 3: \"0 = 1\" doesn't follow"),
+        (proof.replace("1 + script 2", "2 + script 2"), "step 2: step 2 isn't before this one"),
+        (proof.replace("script 3", "script 4"), "step 3: script 4 doesn't exist"),
+        (proof.replace("steps 3", "steps 4"), "proof ends after 3 of 4 steps"),
+        ("steps 1\n1: script 1 => i_1 XOR i_3 = 1".to_owned(), "the last step isn't 0 = 1"),
+        ("1: script 1 => 0 = 1".to_owned(), "proof has to start with \"steps <count>\""),
+    ];
+    for (proof, error) in cases {
+        assert_eq!(check(UNSAT, &proof), Err(error.to_owned()));
+    }
+
+    // a valid proof for another input
+    assert!(check("3 3\n2 1 3\n2 2 3\n1 2\n", &proof).is_err());
+}
+
+#[test]
+fn cli() {
+    let input = write_input("input", UNSAT);
+    let proof = TempFile::new("written");
+    let binary = env!("CARGO_BIN_EXE_konfiguracni-retezec");
+
+    let output = Command::new(binary).args([input.to_str().unwrap(), "--proof", proof.to_str().unwrap()]).output().unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "0\n");
+
+    let output = Command::new(binary).args(["check", input.to_str().unwrap(), proof.to_str().unwrap()]).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "ok, 3 steps\n");
+
+    let wrong = write_input("wrong", "steps 1\n1: script 1 => 0 = 1\n");
+    let output = Command::new(binary).args(["check", input.to_str().unwrap(), wrong.to_str().unwrap()]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "Err: step 1: \"0 = 1\" doesn't follow\n");
+}
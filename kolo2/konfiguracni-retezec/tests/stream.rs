@@ -0,0 +1,63 @@
+use std::io::{BufReader, Read, Write};
+use std::process::{Command, Stdio};
+use konfiguracni_retezec::random::{Generator, Rng};
+use konfiguracni_retezec::System;
+
+/// Hands out the input a few bytes at a time, so lines end up split between reads
+struct Trickle<'a> {
+    input: &'a [u8],
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = buf.len().min(self.input.len()).min(3);
+        buf[..read].copy_from_slice(&self.input[..read]);
+        self.input = &self.input[read..];
+        Ok(read)
+    }
+}
+
+#[test]
+fn read_matches_the_instance() {
+    for seed in 0..200 {
+        let mut rng = Rng::new(seed);
+        let generator = Generator {
+            switches: rng.below(30) as usize,
+            scripts: rng.below(30) as usize,
+            density: 0.3,
+            planted: false,
+        };
+        let instance = generator.generate(&mut rng);
+        let input = instance.to_input();
+
+        let read = System::read(BufReader::with_capacity(4, Trickle { input: input.as_bytes() })).unwrap();
+        assert_eq!(read, instance.system(), "seed {}", seed);
+    }
+}
+
+#[test]
+fn lenient_lines() {
+    // script listed twice, scripts out of range, garbage, windows line ends
+    let system = System::parse("3 2\r\n2 1 1\r\n3 2 x 7\r\n1 -1\r\n\r\nnames\r\nswitch 1 a\r\n").unwrap();
+    let mut expected = System::new(3);
+    expected.add_script(&[1]).add_script(&[2]);
+    expected.names.switches.insert(1, "a".to_owned());
+    assert_eq!(system, expected);
+
+    assert_eq!(System::parse("-3 2\n").unwrap_err(), "failed to parse first line");
+    assert_eq!(System::parse("").unwrap_err(), "failed to parse first line");
+}
+
+#[test]
+fn stdin() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_konfiguracni-retezec"))
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"3 2\n1 1\n2 1 2\n2 1 2\n").unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n001\n");
+}
@@ -0,0 +1,70 @@
+use konfiguracni_retezec::oracle::brute_force;
+use konfiguracni_retezec::random::{Generator, Rng};
+use konfiguracni_retezec::witness::{witness, WitnessOrder};
+use konfiguracni_retezec::System;
+
+mod common;
+use common::{output, run, write_input};
+
+#[test]
+fn orders_agree_with_brute_force() {
+    for seed in 0..1000 {
+        let mut rng = Rng::new(seed);
+        let generator = Generator {
+            switches: 1 + rng.below(12) as usize,
+            scripts: 1 + rng.below(10) as usize,
+            density: [0.2, 0.5][rng.below(2) as usize],
+            planted: rng.chance(0.8),
+        };
+        let system = generator.generate(&mut rng).system();
+        // lexicographically sorted
+        let solutions = brute_force(&system);
+
+        let weight = |solution: &Vec<bool>| solution.iter().filter(|bit| **bit).count();
+        let min_weight = solutions.iter().min_by_key(|solution| weight(solution)).cloned();
+
+        assert_eq!(witness(&system, WitnessOrder::LexMin).unwrap(), solutions.first().cloned(), "seed {}: lexmin", seed);
+        assert_eq!(witness(&system, WitnessOrder::LexMax).unwrap(), solutions.last().cloned(), "seed {}: lexmax", seed);
+        assert_eq!(witness(&system, WitnessOrder::MinWeight).unwrap(), min_weight, "seed {}: minweight", seed);
+
+        let random = witness(&system, WitnessOrder::Random(seed)).unwrap();
+        assert_eq!(random.is_some(), !solutions.is_empty(), "seed {}: random", seed);
+        if let Some(random) = random {
+            assert!(solutions.contains(&random), "seed {}: random isn't a solution", seed);
+            assert_eq!(witness(&system, WitnessOrder::Random(seed)).unwrap(), Some(random), "seed {}: random isn't repeatable", seed);
+        }
+    }
+}
+
+#[test]
+fn minweight_is_limited() {
+    let system = System::new(30);
+    assert_eq!(
+        witness(&system, WitnessOrder::MinWeight).unwrap_err(),
+        "minweight tries every solution, it is limited to 24 free switches, the system has 30"
+    );
+    // lexmin and lexmax don't enumerate
+    assert_eq!(witness(&System::new(1000), WitnessOrder::LexMax).unwrap(), Some(vec![true; 1000]));
+}
+
+#[test]
+fn cli() {
+    // i_1 XOR i_2 = 1, i_3 is free
+    let input = write_input("cli", "3 1\n1 1\n1 1\n0\n");
+
+    let run = |args: &[&str]| run(&[&[input.to_str().unwrap()], args].concat());
+    let error = |args: &[&str]| {
+        let output = output(&[&[input.to_str().unwrap()], args].concat());
+        assert_eq!(output.status.code(), Some(1));
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    assert_eq!(run(&["--witness", "lexmin"]), "4\n010\n");
+    assert_eq!(run(&["--witness", "lexmax"]), "4\n101\n");
+    assert_eq!(run(&["--witness", "minweight"]), "4\n010\n");
+    assert_eq!(run(&["--witness", "random", "--seed", "7"]), run(&["--witness", "random", "--seed", "7"]));
+    assert!(run(&["--witness", "lexmax", "--format", "json"]).contains("\"witness\":\"101\""));
+    assert!(error(&["--seed", "7"]).starts_with("--seed only works with --witness random\n"));
+    assert!(error(&["--witness", "lexmin", "--seed", "7"]).starts_with("--seed only works with --witness random\n"));
+    assert!(error(&["--witness", "first"]).starts_with("--witness has to be followed by lexmin, lexmax, minweight or random\n"));
+}